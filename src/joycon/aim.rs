@@ -0,0 +1,306 @@
+//! Camera control for shooters with flick stick and gyro aiming.
+//!
+//! Outputs are [`AimDelta`]s: how much to turn the camera in the frame.
+//!
+//! # Usage
+//! [`AimController`] combines flick stick on the right stick with gyro aiming.
+//! ```no_run
+//! use joycon_rs::prelude::{*, aim::*, imu::*, stick::*};
+//! use std::time::Instant;
+//!
+//! fn aim(
+//!     standard_full_mode: &StandardFullMode<SimpleJoyConDriver>,
+//!     stick_calibrator: &StickCalibrator,
+//!     imu_calibrator: &IMUCalibrator,
+//! ) -> JoyConResult<()> {
+//!     let mut aim = AimController::new();
+//!     aim.gyro_aim = Some(GyroAim::new(GyroSpace::Player));
+//!
+//!     let mut previous = Instant::now();
+//!     loop {
+//!         let report = standard_full_mode.read_input_report()?;
+//!         let now = Instant::now();
+//!         let delta = aim.update_report(&report, stick_calibrator, imu_calibrator, now - previous);
+//!         previous = now;
+//!
+//!         println!("Turn the camera by {} degrees right, {} degrees up", delta.yaw, delta.pitch);
+//!     }
+//! }
+//! ```
+//!
+//! [`AimController`]: struct.AimController.html
+//! [`AimDelta`]: struct.AimDelta.html
+
+use super::imu::{IMUCalibrator, IMUSample, Vector3};
+use super::input_report_mode::{standard_full_mode::IMUData, StandardInputReport};
+use super::stick::{StickCalibrator, StickPosition};
+use std::time::Duration;
+
+/// Turn of a camera in degrees. Right and up are positive.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct AimDelta {
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl std::ops::Add for AimDelta {
+    type Output = AimDelta;
+
+    fn add(self, rhs: AimDelta) -> AimDelta {
+        AimDelta {
+            yaw: self.yaw + rhs.yaw,
+            pitch: self.pitch + rhs.pitch,
+        }
+    }
+}
+
+/// Flick stick: tilting the stick turns the camera toward the direction of the stick,
+/// and rotating the tilted stick turns the camera as much as the stick rotated.
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{aim::*, stick::*};
+/// use std::time::Duration;
+///
+/// let mut flick_stick = FlickStick::new();
+/// flick_stick.flick_time = Duration::from_secs(0);
+/// let frame = Duration::from_millis(16);
+///
+/// // Flick to the right
+/// let delta = flick_stick.update(StickPosition::new(1.0, 0.0), frame);
+/// assert!((delta - 90.0).abs() < 1e-3);
+///
+/// // Rotate the stick downward
+/// let delta = flick_stick.update(StickPosition::new(0.0, -1.0), frame);
+/// assert!((delta - 90.0).abs() < 1e-3);
+///
+/// // Release
+/// assert_eq!(flick_stick.update(StickPosition::new(0.0, 0.0), frame), 0.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlickStick {
+    /// Magnitude of the stick to flick.
+    pub threshold: f32,
+    /// Time to turn for a flick. Zero turns at once.
+    pub flick_time: Duration,
+    /// Angle of the stick while tilted, in degrees clockwise from up.
+    angle: Option<f32>,
+    /// Turn of the flick left, and time left for it.
+    flick: Option<(f32, Duration)>,
+}
+
+impl Default for FlickStick {
+    fn default() -> Self {
+        FlickStick::new()
+    }
+}
+
+impl FlickStick {
+    pub fn new() -> Self {
+        FlickStick {
+            threshold: 0.9,
+            flick_time: Duration::from_millis(100),
+            angle: None,
+            flick: None,
+        }
+    }
+
+    /// Yaw to turn in the frame of `dt`, in degrees.
+    pub fn update(&mut self, stick: StickPosition, dt: Duration) -> f32 {
+        let mut yaw = 0.0;
+
+        if stick.magnitude() >= self.threshold {
+            let angle = stick.x.atan2(stick.y).to_degrees();
+            match self.angle {
+                // Flick toward the stick
+                None => self.flick = Some((angle, self.flick_time)),
+                // Turn as much as the stick rotated
+                Some(previous) => yaw += wrap_degrees(angle - previous),
+            }
+            self.angle = Some(angle);
+        } else {
+            self.angle = None;
+        }
+
+        if let Some((left, time_left)) = self.flick.take() {
+            if time_left <= dt {
+                yaw += left;
+            } else {
+                let turn = left * (dt.as_secs_f32() / time_left.as_secs_f32());
+                yaw += turn;
+                self.flick = Some((left - turn, time_left - dt));
+            }
+        }
+
+        yaw
+    }
+}
+
+/// Space of gyro aiming.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GyroSpace {
+    /// Turning around the axes of the controller. Simple, but depends on how the controller is held.
+    Local,
+    /// Yaw around gravity, relaxed toward the controller, and pitch around the controller.
+    /// Works in most ways of holding.
+    Player,
+    /// Yaw around gravity, and pitch around the horizontal axis.
+    World,
+}
+
+/// Gyro aiming: turning the controller turns the camera.
+///
+/// Samples should be aligned by [`IMUCalibrator::set_aligned`].
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{aim::*, imu::*};
+/// use std::time::Duration;
+///
+/// // Tilted 90 degrees to the right, and turning right around gravity at 100dps
+/// let sample = IMUSample {
+///     acceleration: Vector3::new(0.0, 1.0, 0.0),
+///     angular_velocity: Vector3::new(0.0, -100.0, 0.0),
+/// };
+///
+/// let mut world = GyroAim::new(GyroSpace::World);
+/// let delta = world.update(&sample, Duration::from_millis(10));
+/// assert!((delta.yaw - 1.0).abs() < 1e-4);
+///
+/// // Local space takes it as pitch
+/// let mut local = GyroAim::new(GyroSpace::Local);
+/// let delta = local.update(&sample, Duration::from_millis(10));
+/// assert!((delta.pitch - 1.0).abs() < 1e-4);
+/// ```
+///
+/// [`IMUCalibrator::set_aligned`]: ../imu/struct.IMUCalibrator.html#method.set_aligned
+#[derive(Debug, Clone, PartialEq)]
+pub struct GyroAim {
+    pub space: GyroSpace,
+    /// Degrees of camera per degree of controller.
+    pub sensitivity: f32,
+    /// Period between samples passed to `update_samples`.
+    pub sample_period: Duration,
+    /// Upward direction in axes of the controller.
+    up: Option<Vector3>,
+}
+
+impl GyroAim {
+    /// Smoothing of gravity from the accelerometer.
+    const GRAVITY_SMOOTHING: f32 = 0.05;
+    /// How much player space prefers yaw around gravity.
+    const YAW_RELAX_FACTOR: f32 = 1.41;
+
+    pub fn new(space: GyroSpace) -> Self {
+        GyroAim {
+            space,
+            sensitivity: 1.0,
+            sample_period: IMUSample::PERIOD,
+            up: None,
+        }
+    }
+
+    /// Update with samples of a report, and sum their turns.
+    pub fn update_samples(&mut self, samples: &[IMUSample]) -> AimDelta {
+        let period = self.sample_period;
+        IMUSample::fold_in_order(samples, AimDelta::default(), |sum, sample| {
+            sum + self.update(sample, period)
+        })
+    }
+
+    /// Update with a sample measured `dt` after the previous one.
+    /// Gravity is followed with the accelerometer.
+    pub fn update(&mut self, sample: &IMUSample, dt: Duration) -> AimDelta {
+        if let Some(accel) = sample.acceleration.normalized() {
+            let up = match self.up {
+                Some(up) => up + (accel - up).scale(Self::GRAVITY_SMOOTHING),
+                None => accel,
+            };
+            self.up = up.normalized();
+        }
+
+        let up = self.up;
+        self.update_with_up(sample.angular_velocity, up, dt)
+    }
+
+    /// Update with angular velocity in dps and the upward direction in axes of the controller,
+    /// e.g. the opposite of `OrientationFilter::gravity`.
+    pub fn update_with_up(&mut self, gyro: Vector3, up: Option<Vector3>, dt: Duration) -> AimDelta {
+        // Rates turning left, and tilting the front end down
+        let (yaw, pitch) = match (self.space, up.and_then(|up| up.normalized())) {
+            (GyroSpace::Player, Some(up)) => {
+                let world_yaw = gyro.z * up.z + gyro.x * up.x;
+                let max = gyro.z.hypot(gyro.x);
+                let yaw = (world_yaw.abs() * Self::YAW_RELAX_FACTOR).min(max);
+                (yaw.copysign(world_yaw), gyro.y)
+            }
+            (GyroSpace::World, Some(up)) => {
+                let yaw = gyro.dot(&up);
+                // Horizontal axis to the left of the front end
+                let left = up.cross(&Vector3::new(1.0, 0.0, 0.0)).normalized();
+                let pitch = left.map_or(gyro.y, |left| gyro.dot(&left));
+                (yaw, pitch)
+            }
+            _ => (gyro.z, gyro.y),
+        };
+
+        let scale = self.sensitivity * dt.as_secs_f32();
+        AimDelta {
+            yaw: -yaw * scale,
+            pitch: -pitch * scale,
+        }
+    }
+}
+
+/// Flick stick and gyro aiming together.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AimController {
+    /// `None` disables flick stick.
+    pub flick_stick: Option<FlickStick>,
+    /// `None` disables gyro aiming.
+    pub gyro_aim: Option<GyroAim>,
+}
+
+impl AimController {
+    /// Flick stick and no gyro aiming.
+    pub fn new() -> Self {
+        AimController {
+            flick_stick: Some(FlickStick::new()),
+            gyro_aim: None,
+        }
+    }
+
+    /// Turn of the camera in the frame of `dt`, the time since the previous report.
+    ///
+    /// Flick stick uses the right stick, or the left stick of Joy-Con (L).
+    pub fn update_report(
+        &mut self,
+        report: &StandardInputReport<IMUData>,
+        stick_calibrator: &StickCalibrator,
+        imu_calibrator: &IMUCalibrator,
+        dt: Duration,
+    ) -> AimDelta {
+        let mut delta = AimDelta::default();
+
+        if let Some(flick_stick) = &mut self.flick_stick {
+            let common = &report.common;
+            let stick = stick_calibrator
+                .right(&common.right_analog_stick_data)
+                .or_else(|| stick_calibrator.left(&common.left_analog_stick_data));
+            if let Some(stick) = stick {
+                delta.yaw += flick_stick.update(stick, dt);
+            }
+        }
+
+        if let Some(gyro_aim) = &mut self.gyro_aim {
+            delta = delta + gyro_aim.update_samples(&report.extra.samples(imu_calibrator));
+        }
+
+        delta
+    }
+}
+
+/// Wrap into -180 - 180 degrees.
+fn wrap_degrees(angle: f32) -> f32 {
+    (angle + 180.0).rem_euclid(360.0) - 180.0
+}
@@ -0,0 +1,388 @@
+//! Events of buttons from consecutive reports.
+//!
+//! Reports only tell which buttons are pushed.
+//! [`ButtonTracker`] compares them with the previous ones, and reports presses, releases,
+//! long presses, double taps and auto-repeat as [`ButtonEvent`]s.
+//! It works with buttons of [`StandardFullMode`] and [`SimpleHIDMode`] alike.
+//!
+//! # Usage
+//! Keep a tracker per controller, and feed it every report.
+//! ```no_run
+//! use joycon_rs::prelude::{*, button_events::*};
+//! use std::time::Instant;
+//!
+//! fn print_events(standard_full_mode: &StandardFullMode<SimpleJoyConDriver>) -> JoyConResult<()> {
+//!     let mut tracker = ButtonTracker::new(ButtonEventConfig::default());
+//!
+//!     loop {
+//!         let report = standard_full_mode.read_input_report()?;
+//!
+//!         for event in tracker.update_report(&report, Instant::now()) {
+//!             match event.kind {
+//!                 ButtonEventKind::LongPress => println!("{:?} long pressed", event.button),
+//!                 ButtonEventKind::DoubleTap => println!("{:?} double tapped", event.button),
+//!                 _ => {}
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`ButtonTracker`]: struct.ButtonTracker.html
+//! [`ButtonEvent`]: struct.ButtonEvent.html
+//! [`StandardFullMode`]: ../input_report_mode/standard_full_mode/struct.StandardFullMode.html
+//! [`SimpleHIDMode`]: ../input_report_mode/simple_hid_mode/struct.SimpleHIDMode.html
+
+use super::input_report_mode::{
+    simple_hid_mode::{SimpleHIDButton, SimpleHIDReport},
+    ButtonSet, CommonReport, PushedButtons, StandardInputReport,
+};
+use super::*;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Buttons which [`ButtonTracker`] can follow.
+///
+/// [`ButtonTracker`]: struct.ButtonTracker.html
+pub trait TrackedButton: Copy + Eq + Hash {
+    /// Whether `self` stands for `pushed`, a button told by reports.
+    /// A button stands only for itself by default.
+    fn stands_for(&self, pushed: &Self) -> bool {
+        self == pushed
+    }
+}
+
+/// `Buttons::SL` and `Buttons::SR` stand for the button of either side.
+impl TrackedButton for Buttons {
+    fn stands_for(&self, pushed: &Buttons) -> bool {
+        match (self, pushed) {
+            (Buttons::SL, Buttons::LeftSL) | (Buttons::SL, Buttons::RightSL) => true,
+            (Buttons::SR, Buttons::LeftSR) | (Buttons::SR, Buttons::RightSR) => true,
+            _ => self == pushed,
+        }
+    }
+}
+
+impl TrackedButton for SimpleHIDButton {}
+
+/// Reports which tell pushed buttons.
+pub trait PressedButtons {
+    type Button: TrackedButton;
+
+    /// Buttons pushed in the report.
+    fn pressed_buttons(&self) -> Vec<Self::Button>;
+}
+
+impl PressedButtons for PushedButtons {
+    type Button = Buttons;
+
+    fn pressed_buttons(&self) -> Vec<Buttons> {
+        self.right
+            .iter()
+            .chain(self.shared.iter())
+            .chain(self.left.iter())
+            .copied()
+            .collect()
+    }
+}
+
+/// SL and SR are told with their sides, e.g. `Buttons::LeftSL`.
+impl PressedButtons for ButtonSet {
+    type Button = Buttons;
+
+    fn pressed_buttons(&self) -> Vec<Buttons> {
+        self.iter().collect()
+    }
+}
+
+/// SL and SR are told with their sides, e.g. `Buttons::LeftSL`.
+impl PressedButtons for CommonReport {
+    type Button = Buttons;
+
+    fn pressed_buttons(&self) -> Vec<Buttons> {
+        self.buttons().pressed_buttons()
+    }
+}
+
+impl<EX> PressedButtons for StandardInputReport<EX>
+where
+    EX: TryFrom<[u8; 349], Error = JoyConError>,
+{
+    type Button = Buttons;
+
+    fn pressed_buttons(&self) -> Vec<Buttons> {
+        self.common.pressed_buttons()
+    }
+}
+
+impl PressedButtons for SimpleHIDReport {
+    type Button = SimpleHIDButton;
+
+    fn pressed_buttons(&self) -> Vec<SimpleHIDButton> {
+        self.pushed_buttons.clone()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ButtonEventKind {
+    Pressed,
+    /// Released after being held for `held`.
+    Released {
+        held: Duration,
+    },
+    /// Held for `ButtonEventConfig::long_press`. Reported once per press.
+    LongPress,
+    /// Pressed again within `ButtonEventConfig::double_tap` since the previous press.
+    DoubleTap,
+    /// Auto-repeat while held. `count` starts from 1.
+    Repeat {
+        count: u32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ButtonEvent<B> {
+    pub button: B,
+    pub kind: ButtonEventKind,
+    pub timestamp: Instant,
+}
+
+/// Auto-repeat begins after `delay`, and repeats every `interval`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AutoRepeat {
+    pub delay: Duration,
+    pub interval: Duration,
+}
+
+impl Default for AutoRepeat {
+    fn default() -> Self {
+        AutoRepeat {
+            delay: Duration::from_millis(500),
+            interval: Duration::from_millis(100),
+        }
+    }
+}
+
+/// `None` disables each detection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ButtonEventConfig {
+    pub long_press: Option<Duration>,
+    pub double_tap: Option<Duration>,
+    pub auto_repeat: Option<AutoRepeat>,
+}
+
+impl Default for ButtonEventConfig {
+    fn default() -> Self {
+        ButtonEventConfig {
+            long_press: Some(Duration::from_millis(500)),
+            double_tap: Some(Duration::from_millis(300)),
+            auto_repeat: None,
+        }
+    }
+}
+
+/// A button being held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Held {
+    since: Instant,
+    long_pressed: bool,
+    repeats: u32,
+}
+
+/// Turns buttons pushed in consecutive reports into [`ButtonEvent`]s.
+///
+/// Long presses and auto-repeat are checked on each update.
+/// Since `SimpleHIDMode` sends reports only when buttons change, call `tick` periodically with it.
+///
+/// Events of standard input reports tell SL and SR with their sides, e.g. `Buttons::LeftSL`,
+/// while `Buttons::SL` and `Buttons::SR` in queries stand for either side.
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{*, button_events::*};
+/// use std::time::{Duration, Instant};
+///
+/// let mut tracker = ButtonTracker::new(ButtonEventConfig::default());
+/// let start = Instant::now();
+/// let at = |ms| start + Duration::from_millis(ms);
+///
+/// let kinds = |events: Vec<ButtonEvent<Buttons>>| -> Vec<ButtonEventKind> {
+///     events.into_iter().map(|event| event.kind).collect()
+/// };
+///
+/// assert_eq!(kinds(tracker.update(vec![Buttons::A], at(0))), vec![ButtonEventKind::Pressed]);
+/// assert_eq!(
+///     kinds(tracker.update(vec![], at(100))),
+///     vec![ButtonEventKind::Released { held: Duration::from_millis(100) }],
+/// );
+/// assert_eq!(
+///     kinds(tracker.update(vec![Buttons::A], at(200))),
+///     vec![ButtonEventKind::Pressed, ButtonEventKind::DoubleTap],
+/// );
+/// assert_eq!(kinds(tracker.tick(at(700))), vec![ButtonEventKind::LongPress]);
+/// assert_eq!(tracker.held_duration(&Buttons::A, at(900)), Some(Duration::from_millis(700)));
+/// ```
+///
+/// [`ButtonEvent`]: struct.ButtonEvent.html
+#[derive(Debug, Clone)]
+pub struct ButtonTracker<B: TrackedButton = Buttons> {
+    pub config: ButtonEventConfig,
+    held: HashMap<B, Held>,
+    /// Last press of buttons which may be double tapped.
+    last_press: HashMap<B, Instant>,
+}
+
+impl<B: TrackedButton> ButtonTracker<B> {
+    pub fn new(config: ButtonEventConfig) -> Self {
+        ButtonTracker {
+            config,
+            held: HashMap::new(),
+            last_press: HashMap::new(),
+        }
+    }
+
+    /// Update with buttons pushed in a report.
+    pub fn update_report<R>(&mut self, report: &R, timestamp: Instant) -> Vec<ButtonEvent<B>>
+    where
+        R: PressedButtons<Button = B>,
+    {
+        self.update(report.pressed_buttons(), timestamp)
+    }
+
+    /// Update with buttons pushed now.
+    pub fn update<I>(&mut self, pressed: I, timestamp: Instant) -> Vec<ButtonEvent<B>>
+    where
+        I: IntoIterator<Item = B>,
+    {
+        let pressed: Vec<B> = pressed.into_iter().collect();
+        let mut events = Vec::new();
+        let mut event = |button, kind| {
+            events.push(ButtonEvent {
+                button,
+                kind,
+                timestamp,
+            })
+        };
+
+        // Releases
+        let released: Vec<B> = self
+            .held
+            .keys()
+            .filter(|button| !pressed.contains(button))
+            .copied()
+            .collect();
+        for button in released {
+            if let Some(held) = self.held.remove(&button) {
+                let held = timestamp.saturating_duration_since(held.since);
+                event(button, ButtonEventKind::Released { held });
+            }
+        }
+
+        // Presses
+        for &button in &pressed {
+            if self.held.contains_key(&button) {
+                continue;
+            }
+
+            self.held.insert(
+                button,
+                Held {
+                    since: timestamp,
+                    long_pressed: false,
+                    repeats: 0,
+                },
+            );
+            event(button, ButtonEventKind::Pressed);
+
+            if let Some(window) = self.config.double_tap {
+                let double_tapped = self
+                    .last_press
+                    .get(&button)
+                    .is_some_and(|&last| timestamp.saturating_duration_since(last) <= window);
+                if double_tapped {
+                    // A third tap starts over
+                    self.last_press.remove(&button);
+                    event(button, ButtonEventKind::DoubleTap);
+                } else {
+                    self.last_press.insert(button, timestamp);
+                }
+            }
+        }
+
+        events.extend(self.tick(timestamp));
+        events
+    }
+
+    /// Check long presses and auto-repeat of held buttons at `now`.
+    pub fn tick(&mut self, now: Instant) -> Vec<ButtonEvent<B>> {
+        let config = self.config;
+        let mut events = Vec::new();
+
+        for (&button, held) in self.held.iter_mut() {
+            let duration = now.saturating_duration_since(held.since);
+
+            if let Some(long_press) = config.long_press {
+                if !held.long_pressed && duration >= long_press {
+                    held.long_pressed = true;
+                    events.push(ButtonEvent {
+                        button,
+                        kind: ButtonEventKind::LongPress,
+                        timestamp: now,
+                    });
+                }
+            }
+
+            if let Some(repeat) = config.auto_repeat {
+                // Repeats due by now
+                let due = if duration < repeat.delay {
+                    0
+                } else if repeat.interval.as_nanos() == 0 {
+                    1
+                } else {
+                    ((duration - repeat.delay).as_nanos() / repeat.interval.as_nanos()) as u32 + 1
+                };
+
+                while held.repeats < due {
+                    held.repeats += 1;
+                    events.push(ButtonEvent {
+                        button,
+                        kind: ButtonEventKind::Repeat {
+                            count: held.repeats,
+                        },
+                        timestamp: now,
+                    });
+                }
+            }
+        }
+
+        events
+    }
+
+    /// Whether the button is pushed. `Buttons::SL` and `Buttons::SR` are pushed on either side.
+    pub fn is_pressed(&self, button: &B) -> bool {
+        self.held.keys().any(|pushed| button.stands_for(pushed))
+    }
+
+    /// Time the button has been held at `now`. `None` if it isn't pushed.
+    /// `Buttons::SL` and `Buttons::SR` have been held since either side was pushed.
+    pub fn held_duration(&self, button: &B, now: Instant) -> Option<Duration> {
+        self.held
+            .iter()
+            .filter(|(pushed, _)| button.stands_for(pushed))
+            .map(|(_, held)| now.saturating_duration_since(held.since))
+            .max()
+    }
+
+    /// Buttons being held.
+    pub fn pressed(&self) -> impl Iterator<Item = &B> {
+        self.held.keys()
+    }
+}
+
+impl<B: TrackedButton> Default for ButtonTracker<B> {
+    fn default() -> Self {
+        ButtonTracker::new(ButtonEventConfig::default())
+    }
+}
@@ -0,0 +1,348 @@
+//! Chords and sequences of buttons.
+//!
+//! [`Combo`]s are declared, and [`ComboDetector`] reports them as [`ComboEvent`]s:
+//! * Chords: buttons held together, e.g. SL+SR, Minus+Plus held for 2 seconds, or L+R pushed within 100ms.
+//! * Sequences: buttons pushed in order within a time window, e.g. Up, Up, Down, Down.
+//!
+//! # Usage
+//! ```no_run
+//! use joycon_rs::prelude::{*, combo::*};
+//! use std::time::{Duration, Instant};
+//!
+//! fn print_combos(standard_full_mode: &StandardFullMode<SimpleJoyConDriver>) -> JoyConResult<()> {
+//!     let mut detector = ComboDetector::new();
+//!     detector.add_combo(Combo::chord("menu", vec![Buttons::Minus, Buttons::Plus]).held_for(Duration::from_secs(2)));
+//!     detector.add_combo(Combo::sequence(
+//!         "cheat",
+//!         vec![Buttons::Up, Buttons::Up, Buttons::Down, Buttons::Down],
+//!         Duration::from_secs(2),
+//!     ));
+//!
+//!     loop {
+//!         let report = standard_full_mode.read_input_report()?;
+//!
+//!         for event in detector.update_report(&report, Instant::now()) {
+//!             println!("{}", event.name);
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`ComboDetector`]: struct.ComboDetector.html
+//! [`Combo`]: struct.Combo.html
+//! [`ComboEvent`]: struct.ComboEvent.html
+
+use super::button_events::{
+    ButtonEventConfig, ButtonEventKind, ButtonTracker, PressedButtons, TrackedButton,
+};
+use super::*;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComboPattern<B> {
+    /// All of `buttons` held together for `hold`.
+    /// With `within`, the buttons must be pushed within it from the first to the last.
+    /// Other buttons may be held as well.
+    /// `Buttons::SL` and `Buttons::SR` match the button of either side.
+    Chord {
+        buttons: Vec<B>,
+        hold: Duration,
+        within: Option<Duration>,
+    },
+    /// `buttons` pushed in order, from the first to the last within `window`.
+    /// Any other button pushed between them breaks the sequence.
+    Sequence { buttons: Vec<B>, window: Duration },
+}
+
+/// A named [`ComboPattern`].
+///
+/// [`ComboPattern`]: enum.ComboPattern.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Combo<B = Buttons> {
+    pub name: String,
+    pub pattern: ComboPattern<B>,
+}
+
+impl<B> Combo<B> {
+    /// A chord reported as soon as all of `buttons` are held.
+    pub fn chord(name: impl Into<String>, buttons: Vec<B>) -> Self {
+        Combo {
+            name: name.into(),
+            pattern: ComboPattern::Chord {
+                buttons,
+                hold: Duration::from_secs(0),
+                within: None,
+            },
+        }
+    }
+
+    pub fn sequence(name: impl Into<String>, buttons: Vec<B>, window: Duration) -> Self {
+        Combo {
+            name: name.into(),
+            pattern: ComboPattern::Sequence { buttons, window },
+        }
+    }
+
+    /// Require a chord to be held for `hold`. Sequences are left as they are.
+    pub fn held_for(mut self, hold: Duration) -> Self {
+        if let ComboPattern::Chord { hold: h, .. } = &mut self.pattern {
+            *h = hold;
+        }
+        self
+    }
+
+    /// Require buttons of a chord to be pushed within `within`. Sequences are left as they are.
+    pub fn within(mut self, within: Duration) -> Self {
+        if let ComboPattern::Chord { within: w, .. } = &mut self.pattern {
+            *w = Some(within);
+        }
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComboEvent {
+    /// Name of the combo.
+    pub name: String,
+    pub timestamp: Instant,
+}
+
+/// Detects [`Combo`]s in buttons pushed in consecutive reports.
+///
+/// A chord is reported once until any of its buttons is released.
+/// Presses are forgotten after a sequence is reported, so a sequence doesn't overlap the next.
+/// Since `SimpleHIDMode` sends reports only when buttons change, call `tick` periodically
+/// with it to detect chords held for a while.
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{*, combo::*};
+/// use std::time::{Duration, Instant};
+///
+/// let mut detector = ComboDetector::new();
+/// detector.add_combo(Combo::chord("LR", vec![Buttons::L, Buttons::R]).within(Duration::from_millis(100)));
+/// detector.add_combo(Combo::sequence("UD", vec![Buttons::Up, Buttons::Down], Duration::from_secs(1)));
+///
+/// let start = Instant::now();
+/// let at = |ms| start + Duration::from_millis(ms);
+/// let names = |events: Vec<ComboEvent>| -> Vec<String> {
+///     events.into_iter().map(|event| event.name).collect()
+/// };
+///
+/// // L, then R too late
+/// assert!(detector.update(vec![Buttons::L], at(0)).is_empty());
+/// assert!(detector.update(vec![Buttons::L, Buttons::R], at(200)).is_empty());
+///
+/// // L and R together
+/// assert!(detector.update(vec![], at(300)).is_empty());
+/// assert!(detector.update(vec![Buttons::R], at(400)).is_empty());
+/// assert_eq!(names(detector.update(vec![Buttons::L, Buttons::R], at(450))), vec!["LR"]);
+///
+/// // Up, Down
+/// assert!(detector.update(vec![Buttons::Up], at(600)).is_empty());
+/// assert!(detector.update(vec![], at(700)).is_empty());
+/// assert_eq!(names(detector.update(vec![Buttons::Down], at(800))), vec!["UD"]);
+/// ```
+///
+/// Standard input reports tell SL and SR with their sides,
+/// and `Buttons::SL` and `Buttons::SR` in combos match either side.
+/// ```
+/// use joycon_rs::prelude::{*, combo::*};
+/// use joycon_rs::joycon::input_report_mode::ButtonSet;
+/// use std::time::Instant;
+///
+/// let mut detector = ComboDetector::new();
+/// detector.add_combo(Combo::chord("SL+SR", vec![Buttons::SL, Buttons::SR]));
+///
+/// // SL and SR of Joy-Con (L)
+/// let pushed = ButtonSet::from_raw([0b0000_0000, 0b0000_0000, 0b0011_0000]);
+/// assert_eq!(pushed.iter().collect::<Vec<_>>(), vec![Buttons::LeftSR, Buttons::LeftSL]);
+///
+/// let events = detector.update(pushed, Instant::now());
+/// assert_eq!(events.len(), 1);
+/// assert_eq!(events[0].name, "SL+SR");
+/// ```
+///
+/// [`Combo`]: struct.Combo.html
+#[derive(Debug, Clone)]
+pub struct ComboDetector<B: TrackedButton = Buttons> {
+    combos: Vec<Combo<B>>,
+    tracker: ButtonTracker<B>,
+    /// Whether each chord has been reported while held.
+    reported: Vec<bool>,
+    /// Recent presses, oldest first.
+    presses: VecDeque<(B, Instant)>,
+}
+
+impl<B: TrackedButton> Default for ComboDetector<B> {
+    fn default() -> Self {
+        ComboDetector::new()
+    }
+}
+
+impl<B: TrackedButton> ComboDetector<B> {
+    pub fn new() -> Self {
+        let config = ButtonEventConfig {
+            long_press: None,
+            double_tap: None,
+            auto_repeat: None,
+        };
+
+        ComboDetector {
+            combos: Vec::new(),
+            tracker: ButtonTracker::new(config),
+            reported: Vec::new(),
+            presses: VecDeque::new(),
+        }
+    }
+
+    pub fn combos(&self) -> &[Combo<B>] {
+        &self.combos
+    }
+
+    /// Add a combo. Combos without buttons are ignored.
+    pub fn add_combo(&mut self, combo: Combo<B>) {
+        let empty = match &combo.pattern {
+            ComboPattern::Chord { buttons, .. } => buttons.is_empty(),
+            ComboPattern::Sequence { buttons, .. } => buttons.is_empty(),
+        };
+
+        if !empty {
+            self.combos.push(combo);
+            self.reported.push(false);
+        }
+    }
+
+    /// Remove combos named `name`.
+    pub fn remove_combo(&mut self, name: &str) {
+        let mut reported = self.reported.iter();
+        let mut kept = Vec::new();
+        self.combos.retain(|combo| {
+            let was_reported = reported.next().copied().unwrap_or(false);
+            let keep = combo.name != name;
+            if keep {
+                kept.push(was_reported);
+            }
+            keep
+        });
+        self.reported = kept;
+    }
+
+    /// Update with buttons pushed in a report.
+    pub fn update_report<R>(&mut self, report: &R, timestamp: Instant) -> Vec<ComboEvent>
+    where
+        R: PressedButtons<Button = B>,
+    {
+        self.update(report.pressed_buttons(), timestamp)
+    }
+
+    /// Update with buttons pushed now.
+    pub fn update<I>(&mut self, pressed: I, timestamp: Instant) -> Vec<ComboEvent>
+    where
+        I: IntoIterator<Item = B>,
+    {
+        let mut events = Vec::new();
+
+        for event in self.tracker.update(pressed, timestamp) {
+            if let ButtonEventKind::Pressed = event.kind {
+                self.presses.push_back((event.button, timestamp));
+                if let Some(name) = self.match_sequence(timestamp) {
+                    self.presses.clear();
+                    events.push(ComboEvent { name, timestamp });
+                }
+            }
+        }
+
+        // Room for the longest sequence
+        let capacity = self
+            .combos
+            .iter()
+            .filter_map(|combo| match &combo.pattern {
+                ComboPattern::Sequence { buttons, .. } => Some(buttons.len()),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+        while self.presses.len() > capacity {
+            self.presses.pop_front();
+        }
+
+        events.extend(self.tick(timestamp));
+        events
+    }
+
+    /// Check chords held for a while at `now`.
+    pub fn tick(&mut self, now: Instant) -> Vec<ComboEvent> {
+        let tracker = &self.tracker;
+        let mut events = Vec::new();
+
+        for (combo, reported) in self.combos.iter().zip(self.reported.iter_mut()) {
+            let (buttons, hold, within) = match &combo.pattern {
+                ComboPattern::Chord {
+                    buttons,
+                    hold,
+                    within,
+                } => (buttons, *hold, *within),
+                _ => continue,
+            };
+
+            let held: Option<Vec<Duration>> = buttons
+                .iter()
+                .map(|button| tracker.held_duration(button, now))
+                .collect();
+            let held = match held {
+                Some(held) => held,
+                None => {
+                    *reported = false;
+                    continue;
+                }
+            };
+
+            // Since the first press, and since the last press
+            let longest = held.iter().max().copied().unwrap_or_default();
+            let shortest = held.iter().min().copied().unwrap_or_default();
+
+            let in_time = within.is_none_or(|within| longest - shortest <= within);
+            if !*reported && in_time && shortest >= hold {
+                *reported = true;
+                events.push(ComboEvent {
+                    name: combo.name.clone(),
+                    timestamp: now,
+                });
+            }
+        }
+
+        events
+    }
+
+    /// Buttons being held.
+    pub fn pressed(&self) -> impl Iterator<Item = &B> {
+        self.tracker.pressed()
+    }
+
+    /// The first sequence which recent presses end with.
+    fn match_sequence(&self, now: Instant) -> Option<String> {
+        self.combos.iter().find_map(|combo| {
+            let (buttons, window) = match &combo.pattern {
+                ComboPattern::Sequence { buttons, window } => (buttons, *window),
+                _ => return None,
+            };
+
+            let skip = self.presses.len().checked_sub(buttons.len())?;
+            let mut recent = self.presses.iter().skip(skip);
+            let (_, first) = recent.clone().next()?;
+
+            let in_order = recent
+                .by_ref()
+                .zip(buttons.iter())
+                .all(|((pressed, _), button)| button.stands_for(pressed));
+
+            if in_order && now.saturating_duration_since(*first) <= window {
+                Some(combo.name.clone())
+            } else {
+                None
+            }
+        })
+    }
+}
@@ -1,5 +1,9 @@
 use super::*;
+use std::cell::{Ref, RefCell};
+use std::collections::VecDeque;
 use std::convert::TryInto;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Instant;
 
 #[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -9,6 +13,64 @@ pub enum JoyConDeviceType {
     ProCon = 2,
 }
 
+impl JoyConDeviceType {
+    /// Whether the device has HOME light. Left Joy-Con doesn't.
+    pub fn has_home_light(&self) -> bool {
+        matches!(self, JoyConDeviceType::JoyConR | JoyConDeviceType::ProCon)
+    }
+}
+
+/// Read SPI flash of the device through sub-command, according to `policy`.
+/// This returns `length` bytes read from `address`.
+fn spi_read(
+    device: &HidDevice,
+    address: u16,
+    length: u8,
+    policy: &RetryPolicy,
+) -> JoyConResult<Vec<u8>> {
+    let [low, high] = address.to_le_bytes();
+    let sub_command = SubCommand::SPIFlashRead as u8;
+    let request = [
+        0x01,
+        0x00,
+        0x00,
+        0x00,
+        0x00,
+        0x00,
+        0x00,
+        0x00,
+        0x00,
+        0x00,
+        sub_command,
+        low,
+        high,
+        0,
+        0,
+        length,
+    ];
+
+    let reply = policy.run(|timeout| {
+        device.write(&request)?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            let now = Instant::now();
+            if deadline <= now {
+                return Ok(None);
+            }
+
+            let mut buf = [0u8; 64];
+            device.read_timeout(&mut buf, (deadline - now).as_millis().max(1) as i32)?;
+
+            if buf[14..20] == [sub_command, low, high, 0, 0, length] {
+                return Ok(Some(buf[20..20 + length as usize].to_vec()));
+            }
+        }
+    })?;
+
+    reply.ok_or(JoyConError::SubCommandTimeout(sub_command))
+}
+
 pub mod calibration {
     use super::*;
 
@@ -131,59 +193,39 @@ pub mod calibration {
         }
 
         pub fn get_factory_calibration(device: &HidDevice) -> Option<JoyConSticksCalibration> {
-            device
-                .write(&[
-                    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x3D, 0x60,
-                    0, 0, 18,
-                ])
-                .ok()?;
-
-            for _ in 0..5 {
-                let mut buf = [0u8; 64];
-                device.read_timeout(&mut buf, 20).ok()?;
-
-                match buf[14..20] {
-                    [0x10, 0x3D, 0x60, 0, 0, 18] => {}
-                    _ => continue,
-                }
+            get_factory_calibration_with_policy(device, &RetryPolicy::default()).ok()
+        }
 
-                let mut report = [0u8; 18];
-                report.copy_from_slice(&buf[20..38]);
+        pub fn get_factory_calibration_with_policy(
+            device: &HidDevice,
+            policy: &RetryPolicy,
+        ) -> JoyConResult<JoyConSticksCalibration> {
+            let data = spi_read(device, 0x603D, 18, policy)?;
 
-                return Some(JoyConSticksCalibration::from(report));
-            }
+            let mut report = [0u8; 18];
+            report.copy_from_slice(&data);
 
-            None
+            Ok(JoyConSticksCalibration::from(report))
         }
 
         pub fn get_user_calibration(device: &HidDevice) -> Option<JoyConSticksCalibration> {
-            device
-                .write(&[
-                    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x12, 0x80,
-                    0, 0, 20,
-                ])
-                .ok()?;
-
-            for _ in 0..5 {
-                let mut buf = [0u8; 64];
-                device.read_timeout(&mut buf, 20).ok()?;
-
-                match buf[14..20] {
-                    [0x10, 0x12, 0x80, 0, 0, 20] => {}
-                    _ => continue,
-                }
-
-                let mut report = [0u8; 18];
-                {
-                    let (left, right) = report.split_at_mut(9);
-                    left.copy_from_slice(&buf[20..29]);
-                    right.copy_from_slice(&buf[31..40]);
-                }
+            get_user_calibration_with_policy(device, &RetryPolicy::default()).ok()
+        }
 
-                return Some(JoyConSticksCalibration::from(report));
+        pub fn get_user_calibration_with_policy(
+            device: &HidDevice,
+            policy: &RetryPolicy,
+        ) -> JoyConResult<JoyConSticksCalibration> {
+            let data = spi_read(device, 0x8012, 20, policy)?;
+
+            let mut report = [0u8; 18];
+            {
+                let (left, right) = report.split_at_mut(9);
+                left.copy_from_slice(&data[0..9]);
+                right.copy_from_slice(&data[11..20]);
             }
 
-            None
+            Ok(JoyConSticksCalibration::from(report))
         }
 
         #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -233,29 +275,19 @@ pub mod calibration {
         }
 
         pub fn get_parameters(device: &HidDevice) -> Option<StickParameters> {
-            device
-                .write(&[
-                    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x86, 0x60,
-                    0, 0, 18,
-                ])
-                .ok()?;
-
-            for _ in 0..5 {
-                let mut buf = [0u8; 64];
-                device.read_timeout(&mut buf, 20).ok()?;
-
-                match buf[14..20] {
-                    [0x10, 0x86, 0x60, 0, 0, 18] => {}
-                    _ => continue,
-                }
+            get_parameters_with_policy(device, &RetryPolicy::default()).ok()
+        }
 
-                let mut report = [0u8; 18];
-                report.copy_from_slice(&buf[20..38]);
+        pub fn get_parameters_with_policy(
+            device: &HidDevice,
+            policy: &RetryPolicy,
+        ) -> JoyConResult<StickParameters> {
+            let data = spi_read(device, 0x6086, 18, policy)?;
 
-                return Some(StickParameters::from(report));
-            }
+            let mut report = [0u8; 18];
+            report.copy_from_slice(&data);
 
-            None
+            Ok(StickParameters::from(report))
         }
     }
 
@@ -359,55 +391,35 @@ pub mod calibration {
         }
 
         pub fn get_factory_calibration(device: &HidDevice) -> Option<IMUCalibration> {
-            device
-                .write(&[
-                    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x20, 0x60,
-                    0, 0, 24,
-                ])
-                .ok()?;
-
-            for _ in 0..5 {
-                let mut buf = [0u8; 64];
-                device.read_timeout(&mut buf, 20).ok()?;
-
-                match buf[14..20] {
-                    [0x10, 0x20, 0x60, 0, 0, 24] => {}
-                    _ => continue,
-                }
+            get_factory_calibration_with_policy(device, &RetryPolicy::default()).ok()
+        }
 
-                let mut report = [0u8; 24];
-                report.copy_from_slice(&buf[20..44]);
+        pub fn get_factory_calibration_with_policy(
+            device: &HidDevice,
+            policy: &RetryPolicy,
+        ) -> JoyConResult<IMUCalibration> {
+            let data = spi_read(device, 0x6020, 24, policy)?;
 
-                return Some(IMUCalibration::from(report));
-            }
+            let mut report = [0u8; 24];
+            report.copy_from_slice(&data);
 
-            None
+            Ok(IMUCalibration::from(report))
         }
 
         pub fn get_user_calibration(device: &HidDevice) -> Option<IMUCalibration> {
-            device
-                .write(&[
-                    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x28, 0x80,
-                    0, 0, 24,
-                ])
-                .ok()?;
-
-            for _ in 0..5 {
-                let mut buf = [0u8; 64];
-                device.read_timeout(&mut buf, 20).ok()?;
-
-                match buf[14..20] {
-                    [0x10, 0x28, 0x80, 0, 0, 24] => {}
-                    _ => continue,
-                }
+            get_user_calibration_with_policy(device, &RetryPolicy::default()).ok()
+        }
 
-                let mut report = [0u8; 24];
-                report.copy_from_slice(&buf[20..44]);
+        pub fn get_user_calibration_with_policy(
+            device: &HidDevice,
+            policy: &RetryPolicy,
+        ) -> JoyConResult<IMUCalibration> {
+            let data = spi_read(device, 0x8028, 24, policy)?;
 
-                return Some(IMUCalibration::from(report));
-            }
+            let mut report = [0u8; 24];
+            report.copy_from_slice(&data);
 
-            None
+            Ok(IMUCalibration::from(report))
         }
 
         #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -428,29 +440,19 @@ pub mod calibration {
         }
 
         pub fn get_offsets(device: &HidDevice) -> Option<IMUOffsets> {
-            device
-                .write(&[
-                    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x80, 0x60,
-                    0, 0, 6,
-                ])
-                .ok()?;
-
-            for _ in 0..5 {
-                let mut buf = [0u8; 64];
-                device.read_timeout(&mut buf, 20).ok()?;
-
-                match buf[14..20] {
-                    [0x10, 0x80, 0x60, 0, 0, 6] => {}
-                    _ => continue,
-                }
+            get_offsets_with_policy(device, &RetryPolicy::default()).ok()
+        }
 
-                let mut report = [0u8; 6];
-                report.copy_from_slice(&buf[20..26]);
+        pub fn get_offsets_with_policy(
+            device: &HidDevice,
+            policy: &RetryPolicy,
+        ) -> JoyConResult<IMUOffsets> {
+            let data = spi_read(device, 0x6080, 6, policy)?;
 
-                return Some(IMUOffsets::from(report));
-            }
+            let mut report = [0u8; 6];
+            report.copy_from_slice(&data);
 
-            None
+            Ok(IMUOffsets::from(report))
         }
     }
 }
@@ -492,29 +494,207 @@ pub mod color {
     }
 
     pub fn get_color(device: &HidDevice) -> Option<Color> {
-        device
-            .write(&[
-                0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x50, 0x60, 0, 0,
-                12,
-            ])
-            .ok()?;
-
-        for _ in 0..5 {
-            let mut buf = [0u8; 64];
-            device.read_timeout(&mut buf, 20).ok()?;
+        get_color_with_policy(device, &RetryPolicy::default()).ok()
+    }
+
+    pub fn get_color_with_policy(device: &HidDevice, policy: &RetryPolicy) -> JoyConResult<Color> {
+        let data = spi_read(device, 0x6050, 12, policy)?;
+
+        let mut report = [0u8; 12];
+        report.copy_from_slice(&data);
+
+        Ok(Color::from(report))
+    }
+}
 
-            match buf[14..20] {
-                [0x10, 0x50, 0x60, 0, 0, 12] => {}
-                _ => continue,
+/// Paces output reports sent to a device.
+///
+/// Joy-Con drops or NACKs output reports sent faster than roughly one every 15ms.
+/// The scheduler keeps output reports in a queue and hands out a slot to each of them in order,
+/// at least `min_interval` apart.
+/// A queued rumble-only report is superseded by a newer rumble-only report, so it is not sent.
+///
+/// A scheduler belongs to a [`JoyConDevice`], so it is shared by every driver holding the same device.
+///
+/// [`JoyConDevice`]: ../struct.JoyConDevice.html
+pub mod output_scheduler {
+    use super::*;
+    use std::time::Duration;
+
+    /// What the holder of a ticket should do.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Turn {
+        /// Send the output report now.
+        Ready,
+        /// Wait for the duration and ask again.
+        Wait(Duration),
+        /// The output report was superseded by a newer one. Don't send it.
+        Merged,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Ticket {
+        id: u64,
+        rumble_only: bool,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct OutputScheduler {
+        min_interval: Duration,
+        last_sent: Option<Instant>,
+        queue: VecDeque<Ticket>,
+        next_id: u64,
+    }
+
+    impl Default for OutputScheduler {
+        fn default() -> Self {
+            OutputScheduler::new(Self::DEFAULT_INTERVAL)
+        }
+    }
+
+    impl OutputScheduler {
+        /// Default minimum interval between output reports.
+        pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(15);
+
+        pub fn new(min_interval: Duration) -> Self {
+            OutputScheduler {
+                min_interval,
+                last_sent: None,
+                queue: VecDeque::new(),
+                next_id: 0,
+            }
+        }
+
+        /// Minimum interval between output reports.
+        pub fn min_interval(&self) -> Duration {
+            self.min_interval
+        }
+
+        pub fn set_min_interval(&mut self, min_interval: Duration) {
+            self.min_interval = min_interval;
+        }
+
+        /// Number of output reports waiting for their slot.
+        pub fn queued(&self) -> usize {
+            self.queue.len()
+        }
+
+        /// Whether the output report only updates rumble.
+        pub fn is_rumble_only(report: &[u8]) -> bool {
+            report.first() == Some(&(Command::Rumble as u8))
+        }
+
+        /// Put an output report in the queue, and returns the ticket of it.
+        ///
+        /// If the report is rumble-only, rumble-only reports already queued are superseded,
+        /// and the report takes the place of the oldest of them.
+        ///
+        /// # Example
+        /// ```
+        /// use joycon_rs::joycon::device::output_scheduler::{OutputScheduler, Turn};
+        /// use joycon_rs::prelude::*;
+        ///
+        /// let mut scheduler = OutputScheduler::default();
+        /// let rumble = [Command::Rumble as u8; 0x40];
+        /// let sub_command = [Command::RumbleAndSubCommand as u8; 0x40];
+        ///
+        /// let old_rumble = scheduler.enqueue(&rumble);
+        /// let lights = scheduler.enqueue(&sub_command);
+        /// let new_rumble = scheduler.enqueue(&rumble);
+        ///
+        /// // The newest rumble is still sent before the lights
+        /// assert_eq!(scheduler.turn(old_rumble), Turn::Merged);
+        /// assert_eq!(scheduler.turn(new_rumble), Turn::Ready);
+        /// assert_ne!(scheduler.turn(lights), Turn::Ready);
+        /// ```
+        pub fn enqueue(&mut self, report: &[u8]) -> u64 {
+            let ticket = self.ticket(report);
+
+            let position = if ticket.rumble_only {
+                self.supersede_rumble()
+            } else {
+                None
+            };
+            match position {
+                Some(position) => self.queue.insert(position, ticket),
+                None => self.queue.push_back(ticket),
             }
 
-            let mut report = [0u8; 12];
-            report.copy_from_slice(&buf[20..32]);
+            ticket.id
+        }
+
+        /// Put an output report at the head of the queue, and returns the ticket of it.
+        ///
+        /// This is for a sender holding the device exclusively,
+        /// which cannot wait for the other tickets to be sent.
+        /// If the report is rumble-only, rumble-only reports already queued are superseded.
+        pub fn enqueue_front(&mut self, report: &[u8]) -> u64 {
+            let ticket = self.ticket(report);
+            if ticket.rumble_only {
+                self.supersede_rumble();
+            }
+            self.queue.push_front(ticket);
+
+            ticket.id
+        }
+
+        fn ticket(&mut self, report: &[u8]) -> Ticket {
+            let id = self.next_id;
+            self.next_id = self.next_id.wrapping_add(1);
+
+            Ticket {
+                id,
+                rumble_only: Self::is_rumble_only(report),
+            }
+        }
+
+        /// Remove queued rumble-only tickets, and returns the position of the oldest of them.
+        fn supersede_rumble(&mut self) -> Option<usize> {
+            let position = self.queue.iter().position(|ticket| ticket.rumble_only)?;
+            self.queue.retain(|ticket| !ticket.rumble_only);
+
+            Some(position)
+        }
+
+        /// Until when the next output report has to wait.
+        pub fn next_slot(&self) -> Option<Instant> {
+            self.last_sent
+                .map(|last_sent| last_sent + self.min_interval)
+        }
+
+        /// Ask whether the output report of the ticket can be sent.
+        pub fn turn(&self, ticket: u64) -> Turn {
+            let position = match self.queue.iter().position(|t| t.id == ticket) {
+                Some(position) => position,
+                None => return Turn::Merged,
+            };
+
+            let now = Instant::now();
+            let until_slot = self
+                .next_slot()
+                .map(|slot| slot.saturating_duration_since(now))
+                .unwrap_or_else(|| Duration::from_millis(0));
+
+            if position == 0 && until_slot == Duration::from_millis(0) {
+                Turn::Ready
+            } else if position == 0 {
+                Turn::Wait(until_slot)
+            } else {
+                // Wait for the preceding reports
+                Turn::Wait(until_slot.max(Duration::from_millis(1)))
+            }
+        }
 
-            return Some(Color::from(report));
+        /// Notify that the output report of the ticket was sent (or failed to be sent).
+        pub fn sent(&mut self, ticket: u64) {
+            self.queue.retain(|t| t.id != ticket);
+            self.last_sent = Some(Instant::now());
         }
 
-        None
+        /// Remove the ticket from the queue without sending.
+        pub fn cancel(&mut self, ticket: u64) {
+            self.queue.retain(|t| t.id != ticket);
+        }
     }
 }
 
@@ -529,6 +709,15 @@ pub struct JoyConDevice {
     imu_factory_calibration: calibration::imu::IMUCalibration,
     imu_user_calibration: calibration::imu::IMUCalibration,
     color: color::Color,
+    /// Input reports set aside while waiting for a sub-command reply.
+    forwarded_reports: RefCell<VecDeque<Vec<u8>>>,
+    /// Sub-command replies read by report consumers, kept for the sender waiting for them.
+    kept_replies: VecDeque<Vec<u8>>,
+    output_scheduler: RefCell<output_scheduler::OutputScheduler>,
+    /// Shared by every sender of output reports to the device.
+    global_packet_number: GlobalPacketNumber,
+    /// Rumble sent with output reports, shared like the packet number.
+    rumble_status: (Option<Rumble>, Option<Rumble>),
 }
 
 impl JoyConDevice {
@@ -536,6 +725,10 @@ impl JoyConDevice {
     pub const PRODUCT_ID_JOYCON_L: u16 = 8198;
     pub const PRODUCT_ID_JOYCON_R: u16 = 8199;
     pub const PRODUCT_ID_PROCON: u16 = 8201;
+    /// Max number of forwarded input reports kept. Older ones are discarded.
+    pub const FORWARDED_REPORTS_CAPACITY: usize = 64;
+    /// Max number of kept sub-command replies. Older ones are discarded.
+    pub const KEPT_REPLIES_CAPACITY: usize = 8;
 
     pub fn check_type_of_device(device_info: &DeviceInfo) -> JoyConResult<JoyConDeviceType> {
         if device_info.vendor_id() != JoyConDevice::VENDOR_ID {
@@ -582,6 +775,11 @@ impl JoyConDevice {
         &self.imu_user_calibration
     }
 
+    /// Calibrator of analog sticks with the calibration of the device.
+    pub fn stick_calibrator(&self) -> crate::joycon::stick::StickCalibrator {
+        crate::joycon::stick::StickCalibrator::new(self)
+    }
+
     pub fn color(&self) -> &color::Color {
         &self.color
     }
@@ -603,33 +801,96 @@ impl JoyConDevice {
         self.device_type.clone()
     }
 
+    /// Whether the device has HOME light.
+    pub fn has_home_light(&self) -> bool {
+        self.device_type.has_home_light()
+    }
+
     pub fn reset_device(&mut self, hid_device: HidDevice) {
         self.hid_device = Some(hid_device);
     }
 
+    /// Take over the HID device and the calibrations of `device`, newly opened for the same controller.
+    ///
+    /// The output scheduler, the packet number and the rumble status are kept,
+    /// so that senders still waiting for their turn are not mixed up with new ones.
+    pub fn reset_device_from(&mut self, device: JoyConDevice) {
+        let JoyConDevice {
+            hid_device,
+            stick_parameters,
+            stick_factory_calibration,
+            stick_user_calibration,
+            imu_offsets,
+            imu_factory_calibration,
+            imu_user_calibration,
+            color,
+            ..
+        } = device;
+
+        self.hid_device = hid_device;
+        self.stick_parameters = stick_parameters;
+        self.stick_factory_calibration = stick_factory_calibration;
+        self.stick_user_calibration = stick_user_calibration;
+        self.imu_offsets = imu_offsets;
+        self.imu_factory_calibration = imu_factory_calibration;
+        self.imu_user_calibration = imu_user_calibration;
+        self.color = color;
+    }
+
     pub fn forget_device(&mut self) {
         self.hid_device = None;
+        self.forwarded_reports.get_mut().clear();
+        self.kept_replies.clear();
     }
 
     pub fn new(device_info: &DeviceInfo, hidapi: &HidApi) -> JoyConResult<Self> {
+        Self::with_retry_policy(device_info, hidapi, &RetryPolicy::default())
+    }
+
+    /// Constructs a new `JoyConDevice`,
+    /// loading calibrations and colors according to the timeout and retry policy.
+    pub fn with_retry_policy(
+        device_info: &DeviceInfo,
+        hidapi: &HidApi,
+        policy: &RetryPolicy,
+    ) -> JoyConResult<Self> {
+        /// Timeouts are reported as they are.
+        fn loading_error(error: JoyConDeviceError) -> impl FnOnce(JoyConError) -> JoyConError {
+            move |e| match e {
+                JoyConError::SubCommandTimeout(_) => e,
+                _ => error.into(),
+            }
+        }
+
         let device_type = Self::check_type_of_device(device_info)?;
 
         let serial = device_info.serial_number().unwrap_or("");
         let hid_device =
             hidapi.open_serial(device_info.vendor_id(), device_info.product_id(), serial)?;
-        let stick_parameters = calibration::stick::get_parameters(&hid_device)
-            .ok_or(JoyConDeviceError::FailedStickParameterLoading)?;
-        let stick_factory_calibration = calibration::stick::get_factory_calibration(&hid_device)
-            .ok_or(JoyConDeviceError::FailedStickCalibrationLoading)?;
-        let stick_user_calibration = calibration::stick::get_user_calibration(&hid_device)
-            .ok_or(JoyConDeviceError::FailedStickCalibrationLoading)?;
-        let imu_offsets = calibration::imu::get_offsets(&hid_device)
-            .ok_or(JoyConDeviceError::FailedIMUOffsetsLoading)?;
-        let imu_factory_calibration = calibration::imu::get_factory_calibration(&hid_device)
-            .ok_or(JoyConDeviceError::FailedIMUCalibrationLoading)?;
-        let imu_user_calibration = calibration::imu::get_user_calibration(&hid_device)
-            .ok_or(JoyConDeviceError::FailedIMUCalibrationLoading)?;
-        let color = color::get_color(&hid_device).ok_or(JoyConDeviceError::FailedColorLoading)?;
+        let stick_parameters = calibration::stick::get_parameters_with_policy(&hid_device, policy)
+            .map_err(loading_error(
+                JoyConDeviceError::FailedStickParameterLoading,
+            ))?;
+        let stick_factory_calibration =
+            calibration::stick::get_factory_calibration_with_policy(&hid_device, policy).map_err(
+                loading_error(JoyConDeviceError::FailedStickCalibrationLoading),
+            )?;
+        let stick_user_calibration =
+            calibration::stick::get_user_calibration_with_policy(&hid_device, policy).map_err(
+                loading_error(JoyConDeviceError::FailedStickCalibrationLoading),
+            )?;
+        let imu_offsets = calibration::imu::get_offsets_with_policy(&hid_device, policy)
+            .map_err(loading_error(JoyConDeviceError::FailedIMUOffsetsLoading))?;
+        let imu_factory_calibration =
+            calibration::imu::get_factory_calibration_with_policy(&hid_device, policy).map_err(
+                loading_error(JoyConDeviceError::FailedIMUCalibrationLoading),
+            )?;
+        let imu_user_calibration =
+            calibration::imu::get_user_calibration_with_policy(&hid_device, policy).map_err(
+                loading_error(JoyConDeviceError::FailedIMUCalibrationLoading),
+            )?;
+        let color = color::get_color_with_policy(&hid_device, policy)
+            .map_err(loading_error(JoyConDeviceError::FailedColorLoading))?;
 
         Ok(JoyConDevice {
             hid_device: Some(hid_device),
@@ -642,18 +903,203 @@ impl JoyConDevice {
             imu_factory_calibration,
             imu_user_calibration,
             color,
+            forwarded_reports: RefCell::new(VecDeque::new()),
+            kept_replies: VecDeque::new(),
+            output_scheduler: RefCell::new(output_scheduler::OutputScheduler::default()),
+            global_packet_number: GlobalPacketNumber::default(),
+            rumble_status: (None, None),
         })
     }
 
+    /// Scheduler pacing output reports to the device.
+    pub fn output_scheduler(&self) -> Ref<'_, output_scheduler::OutputScheduler> {
+        self.output_scheduler.borrow()
+    }
+
+    pub fn output_scheduler_mut(&mut self) -> &mut output_scheduler::OutputScheduler {
+        self.output_scheduler.get_mut()
+    }
+
+    /// Packet number of the next output report.
+    pub fn global_packet_number(&self) -> GlobalPacketNumber {
+        self.global_packet_number
+    }
+
+    /// Take the packet number for an output report, and advance the counter.
+    ///
+    /// Drivers, rumble sequencers and light animators holding the device share the counter,
+    /// so take the packet number here instead of counting separately.
+    pub fn take_global_packet_number(&mut self) -> GlobalPacketNumber {
+        let packet_number = self.global_packet_number;
+        self.global_packet_number = packet_number.next();
+        packet_number
+    }
+
+    /// Rumble sent with output reports.
+    pub fn rumble_status(&self) -> (Option<Rumble>, Option<Rumble>) {
+        self.rumble_status
+    }
+
+    /// Set rumble sent with the following output reports.
+    /// Like the packet number, this is shared by everything sending output reports to the device.
+    pub fn set_rumble_status(&mut self, rumble_l_r: (Option<Rumble>, Option<Rumble>)) {
+        self.rumble_status = rumble_l_r;
+    }
+
+    /// Build an output report with the shared rumble status, taking the packet number.
+    pub fn output_report(&mut self, command: u8, sub_command: u8, data: &[u8]) -> [u8; 0x40] {
+        let packet_number = self.take_global_packet_number();
+
+        driver::output_report(
+            command,
+            packet_number.into(),
+            self.rumble_status,
+            sub_command,
+            data,
+        )
+    }
+
+    /// Send an output report.
+    ///
+    /// Since nothing else can be sent while the device is borrowed,
+    /// the output report goes ahead of the ones queued by [`write_paced`],
+    /// and this only waits for `min_interval` since the last one.
+    /// Consider [`write_paced`] if the device is shared.
+    ///
+    /// [`write_paced`]: #method.write_paced
     pub fn write(&self, data: &[u8]) -> JoyConResult<usize> {
-        if let Some(hid_device) = &self.hid_device {
-            Ok(hid_device.write(data)?)
+        let ticket = self.output_scheduler.borrow_mut().enqueue_front(data);
+
+        loop {
+            let turn = self.output_scheduler.borrow().turn(ticket);
+            match turn {
+                output_scheduler::Turn::Ready => return self.write_now(ticket, data),
+                output_scheduler::Turn::Wait(duration) => std::thread::sleep(duration),
+                output_scheduler::Turn::Merged => return Ok(0),
+            }
+        }
+    }
+
+    /// Send an output report to the shared device.
+    ///
+    /// This waits for the slot of the output report without keeping the device locked,
+    /// so that other drivers can queue their output reports meanwhile.
+    /// If a rumble-only report is superseded by a newer one while waiting, it is not sent,
+    /// and this returns `Ok(0)`.
+    pub fn write_paced(device: &Mutex<JoyConDevice>, data: &[u8]) -> JoyConResult<usize> {
+        let ticket = Self::lock(device).output_scheduler_mut().enqueue(data);
+
+        loop {
+            let turn = {
+                let device = Self::lock(device);
+                let turn = device.output_scheduler.borrow().turn(ticket);
+                match turn {
+                    output_scheduler::Turn::Ready => return device.write_now(ticket, data),
+                    turn => turn,
+                }
+            };
+
+            match turn {
+                output_scheduler::Turn::Wait(duration) => std::thread::sleep(duration),
+                _ => return Ok(0),
+            }
+        }
+    }
+
+    /// Send an output report to the shared device like [`write_paced`],
+    /// with the shared packet number and rumble status.
+    ///
+    /// [`write_paced`]: #method.write_paced
+    pub fn send_paced(
+        device: &Mutex<JoyConDevice>,
+        command: u8,
+        sub_command: u8,
+        data: &[u8],
+    ) -> JoyConResult<usize> {
+        let report = Self::lock(device).output_report(command, sub_command, data);
+
+        Self::write_paced(device, &report)
+    }
+
+    /// Set the shared rumble status, and send it to the shared device like [`write_paced`].
+    ///
+    /// [`write_paced`]: #method.write_paced
+    pub fn rumble_paced(
+        device: &Mutex<JoyConDevice>,
+        rumble_l_r: (Option<Rumble>, Option<Rumble>),
+    ) -> JoyConResult<usize> {
+        let report = {
+            let mut device = Self::lock(device);
+            device.set_rumble_status(rumble_l_r);
+            device.output_report(Command::Rumble as u8, 0, &[])
+        };
+
+        Self::write_paced(device, &report)
+    }
+
+    fn lock(device: &Mutex<JoyConDevice>) -> MutexGuard<'_, JoyConDevice> {
+        match device.lock() {
+            Ok(d) => d,
+            Err(e) => e.into_inner(),
+        }
+    }
+
+    fn write_now(&self, ticket: u64, data: &[u8]) -> JoyConResult<usize> {
+        let res = if let Some(hid_device) = &self.hid_device {
+            hid_device.write(data).map_err(JoyConError::from)
         } else {
             Err(JoyConError::Disconnected)
+        };
+        self.output_scheduler.borrow_mut().sent(ticket);
+
+        res
+    }
+
+    /// Stop rumbling of both sides.
+    pub fn stop_rumble(&mut self) -> JoyConResult<usize> {
+        self.set_rumble_status((Some(Rumble::stop()), Some(Rumble::stop())));
+        let report = self.output_report(Command::Rumble as u8, 0, &[]);
+
+        self.write(&report)
+    }
+
+    /// Hand an input report back, so that it will be returned by the next `read` or `read_timeout`.
+    ///
+    /// This is used to keep input reports received while waiting for a sub-command reply.
+    pub fn forward_report(&mut self, report: &[u8]) {
+        let forwarded_reports = self.forwarded_reports.get_mut();
+        if forwarded_reports.len() >= Self::FORWARDED_REPORTS_CAPACITY {
+            forwarded_reports.pop_front();
+        }
+        forwarded_reports.push_back(report.to_vec());
+    }
+
+    /// Keep a sub-command reply read by a report consumer,
+    /// so that the sender waiting for it takes it with [`take_reply`].
+    ///
+    /// [`take_reply`]: #method.take_reply
+    pub fn keep_reply(&mut self, report: &[u8]) {
+        if self.kept_replies.len() >= Self::KEPT_REPLIES_CAPACITY {
+            self.kept_replies.pop_front();
         }
+        self.kept_replies.push_back(report.to_vec());
     }
 
+    /// Take the oldest kept reply to `sub_command`.
+    pub fn take_reply(&mut self, sub_command: u8) -> Option<Vec<u8>> {
+        let index = self
+            .kept_replies
+            .iter()
+            .position(|reply| reply.get(14) == Some(&sub_command))?;
+        self.kept_replies.remove(index)
+    }
+
+    /// Read an input report. Forwarded input reports are returned first.
     pub fn read(&self, buf: &mut [u8]) -> JoyConResult<usize> {
+        if let Some(res) = self.read_forwarded(buf) {
+            return Ok(res);
+        }
+
         if let Some(hid_device) = &self.hid_device {
             let res = hid_device.read(buf)?;
 
@@ -667,8 +1113,14 @@ impl JoyConDevice {
         }
     }
 
+    /// Read an input report. Forwarded input reports are returned first.
+    ///
     /// * timeout - milli seconds
     pub fn read_timeout(&self, buf: &mut [u8], timeout: i32) -> JoyConResult<usize> {
+        if let Some(res) = self.read_forwarded(buf) {
+            return Ok(res);
+        }
+
         if let Some(hid_device) = &self.hid_device {
             let res = hid_device.read_timeout(buf, timeout)?;
 
@@ -681,6 +1133,15 @@ impl JoyConDevice {
             Err(JoyConError::Disconnected)
         }
     }
+
+    fn read_forwarded(&self, buf: &mut [u8]) -> Option<usize> {
+        let report = self.forwarded_reports.borrow_mut().pop_front()?;
+        let len = report.len().min(buf.len());
+        buf[..len].copy_from_slice(&report[..len]);
+        buf[len..].iter_mut().for_each(|b| *b = 0);
+
+        Some(len)
+    }
 }
 
 impl Debug for JoyConDevice {
@@ -0,0 +1,496 @@
+//! Convert PCM audio into rumble.
+//!
+//! HD rumble is a pair of narrow-band actuators, so audio is reduced to
+//! the dominant frequency and envelope of each band per frame.
+//! The frames can be played through [`RumbleSequencer`].
+//!
+//! # Usage
+//! [`AudioRumbleConverter`] turns a clip into a pattern for [`RumbleSequencer`].
+//! Audio arriving in chunks, such as game sounds, can be converted with [`AudioRumbleStream`].
+//! ```no_run
+//! use joycon_rs::prelude::{*, audio_rumble::*, rumble_sequencer::*};
+//!
+//! fn play_wav(sequencer: &RumbleSequencer) -> JoyConResult<()> {
+//!     let clip = AudioClip::from_wav_file("explosion.wav")?;
+//!     let pattern = AudioRumbleConverter::default().pattern(&clip);
+//!
+//!     sequencer.play(pattern).join()
+//! }
+//! ```
+//!
+//! [`AudioRumbleConverter`]: struct.AudioRumbleConverter.html
+//! [`RumbleSequencer`]: ../rumble_sequencer/struct.RumbleSequencer.html
+//! [`AudioRumbleStream`]: struct.AudioRumbleStream.html
+
+use super::rumble_sequencer::{RumbleFrame, RumblePattern, RumbleTrack};
+use super::*;
+use std::path::Path;
+use std::time::Duration;
+
+/// Mono PCM audio. Samples are in -1.0..=1.0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioClip {
+    sample_rate: u32,
+    samples: Vec<f32>,
+}
+
+impl AudioClip {
+    /// Constructs a clip from mono samples.
+    pub fn from_samples(sample_rate: u32, samples: Vec<f32>) -> Self {
+        AudioClip {
+            sample_rate,
+            samples,
+        }
+    }
+
+    /// Constructs a clip from interleaved samples, mixing the channels down to mono.
+    pub fn from_interleaved(sample_rate: u32, channels: u16, samples: &[f32]) -> Self {
+        let channels = channels.max(1) as usize;
+        let samples = samples
+            .chunks(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect();
+
+        Self::from_samples(sample_rate, samples)
+    }
+
+    /// Constructs a clip from interleaved 16-bit samples, mixing the channels down to mono.
+    pub fn from_interleaved_i16(sample_rate: u32, channels: u16, samples: &[i16]) -> Self {
+        let samples = samples
+            .iter()
+            .map(|&s| s as f32 / 32768.0)
+            .collect::<Vec<_>>();
+
+        Self::from_interleaved(sample_rate, channels, &samples)
+    }
+
+    /// Parses a RIFF WAVE file of integer PCM (8, 16, 24 or 32 bits) or 32-bit float.
+    ///
+    /// # Example
+    /// ```
+    /// use joycon_rs::prelude::audio_rumble::AudioClip;
+    ///
+    /// // 16-bit stereo, 8 kHz, 2 frames
+    /// let mut wav = Vec::new();
+    /// wav.extend_from_slice(b"RIFF\x2C\x00\x00\x00WAVE");
+    /// wav.extend_from_slice(b"fmt \x10\x00\x00\x00");
+    /// wav.extend_from_slice(&[1, 0, 2, 0, 0x40, 0x1F, 0, 0, 0, 0x7D, 0, 0, 4, 0, 16, 0]);
+    /// wav.extend_from_slice(b"data\x08\x00\x00\x00");
+    /// wav.extend_from_slice(&[0x00, 0x40, 0x00, 0x40, 0x00, 0xC0, 0x00, 0x00]);
+    ///
+    /// let clip = AudioClip::from_wav_bytes(&wav).unwrap();
+    /// assert_eq!(clip.sample_rate(), 8000);
+    /// assert_eq!(clip.samples(), &vec![0.5, -0.25]);
+    /// ```
+    pub fn from_wav_bytes(bytes: &[u8]) -> Result<Self, AudioError> {
+        wav::parse(bytes)
+    }
+
+    /// Reads a RIFF WAVE file. See [`from_wav_bytes`].
+    ///
+    /// [`from_wav_bytes`]: #method.from_wav_bytes
+    pub fn from_wav_file<P: AsRef<Path>>(path: P) -> Result<Self, AudioError> {
+        let bytes = std::fs::read(path)?;
+        Self::from_wav_bytes(&bytes)
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn samples(&self) -> &Vec<f32> {
+        &self.samples
+    }
+
+    pub fn duration(&self) -> Duration {
+        if self.sample_rate == 0 {
+            return Duration::from_millis(0);
+        }
+
+        Duration::from_secs_f64(self.samples.len() as f64 / self.sample_rate as f64)
+    }
+}
+
+/// Converts audio into rumble frame by frame.
+///
+/// In each frame, the strongest frequency of each band is picked
+/// from candidates spaced evenly on a log scale,
+/// and its magnitude becomes the amplitude of the band.
+/// A full scale sine wave maps to `max_amplitude`.
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{*, audio_rumble::*};
+///
+/// // 100 ms of 160 Hz
+/// let sample_rate = 8000;
+/// let samples = (0..800)
+///     .map(|i| (2.0 * std::f32::consts::PI * 160.0 * i as f32 / sample_rate as f32).sin())
+///     .collect();
+/// let clip = AudioClip::from_samples(sample_rate, samples);
+///
+/// let track = AudioRumbleConverter::default().track(&clip);
+/// assert_eq!(track.frames().len(), 5);
+///
+/// let low = track.frames()[2].rumble.low();
+/// assert!((low.frequency() - 160.0).abs() < 10.0);
+/// assert!(low.amplitude() > 0.8);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioRumbleConverter {
+    /// Length of a frame. Shorter frames follow the envelope closely but resolve frequencies coarsely.
+    pub frame: Duration,
+    /// Amplitude of a band when the audio is a full scale sine wave.
+    pub max_amplitude: f32,
+    /// Number of candidate frequencies per band.
+    pub resolution: usize,
+}
+
+impl Default for AudioRumbleConverter {
+    fn default() -> Self {
+        AudioRumbleConverter {
+            frame: Duration::from_millis(20),
+            max_amplitude: Rumble::SAFE_AMPLITUDE,
+            resolution: 32,
+        }
+    }
+}
+
+impl AudioRumbleConverter {
+    /// Dominant frequency and amplitude of each band in the samples.
+    pub fn convert_frame(&self, sample_rate: u32, samples: &[f32]) -> Rumble {
+        let band = |min: f32, max: f32| {
+            let (frequency, magnitude) = self.dominant(sample_rate, samples, min, max);
+            RumbleBand::new(frequency, magnitude.min(1.0) * self.max_amplitude)
+        };
+
+        Rumble::dual_band(
+            band(
+                RumbleBand::HIGH_BAND_MIN_FREQUENCY,
+                RumbleBand::HIGH_BAND_MAX_FREQUENCY,
+            ),
+            band(
+                RumbleBand::LOW_BAND_MIN_FREQUENCY,
+                RumbleBand::LOW_BAND_MAX_FREQUENCY,
+            ),
+        )
+    }
+
+    /// Rumble frames of the clip. The last frame may be shorter than `frame`.
+    pub fn frames<'a>(&'a self, clip: &'a AudioClip) -> impl Iterator<Item = RumbleFrame> + 'a {
+        let frame_len = self.frame_len(clip.sample_rate);
+
+        // A clip without sample rate has no duration
+        let samples = if clip.sample_rate == 0 {
+            &clip.samples[..0]
+        } else {
+            &clip.samples[..]
+        };
+
+        samples.chunks(frame_len).map(move |samples| RumbleFrame {
+            rumble: self.convert_frame(clip.sample_rate, samples),
+            duration: Duration::from_secs_f64(samples.len() as f64 / clip.sample_rate as f64),
+            envelope: Default::default(),
+        })
+    }
+
+    /// Track of the clip.
+    pub fn track(&self, clip: &AudioClip) -> RumbleTrack {
+        let mut track = RumbleTrack::new();
+        self.frames(clip).for_each(|frame| track.push_frame(frame));
+        track
+    }
+
+    /// Pattern rumbling both sides with the clip.
+    pub fn pattern(&self, clip: &AudioClip) -> RumblePattern {
+        let track = self.track(clip);
+        RumblePattern::new(Some(track.clone()), Some(track))
+    }
+
+    /// Convert audio arriving in chunks, e.g. from a game's mixer. See [`AudioRumbleStream`].
+    ///
+    /// [`AudioRumbleStream`]: struct.AudioRumbleStream.html
+    pub fn stream(&self, sample_rate: u32, channels: u16) -> AudioRumbleStream {
+        AudioRumbleStream {
+            converter: *self,
+            sample_rate,
+            channels: channels.max(1),
+            partial: Vec::new(),
+            samples: Vec::new(),
+        }
+    }
+
+    /// Number of samples in a frame.
+    fn frame_len(&self, sample_rate: u32) -> usize {
+        ((sample_rate as f64 * self.frame.as_secs_f64()).round() as usize).max(1)
+    }
+
+    /// Strongest candidate frequency in `min..=max` and its magnitude relative to full scale.
+    fn dominant(&self, sample_rate: u32, samples: &[f32], min: f32, max: f32) -> (f32, f32) {
+        let nyquist = sample_rate as f32 / 2.0;
+        let steps = self.resolution.max(2) - 1;
+        let ratio = (max / min).powf(1.0 / steps as f32);
+
+        (0..=steps)
+            .map(|step| min * ratio.powi(step as i32))
+            .filter(|&frequency| frequency < nyquist)
+            .map(|frequency| (frequency, magnitude(sample_rate, samples, frequency)))
+            .fold((min, 0.0), |best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            })
+    }
+}
+
+/// Converts PCM audio fed in chunks into a rumble per frame, as it arrives.
+///
+/// Chunks may end anywhere, even in the middle of a frame or of interleaved channels.
+/// Each `frame` of audio yields a [`Rumble`], which can be sent on each tick
+/// with [`JoyConDriver::rumble`] or [`JoyConDevice::rumble_paced`].
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{*, audio_rumble::*};
+///
+/// // 100 ms of 160 Hz, in chunks of 30 ms
+/// let sample_rate = 8000;
+/// let samples: Vec<f32> = (0..800)
+///     .map(|i| (2.0 * std::f32::consts::PI * 160.0 * i as f32 / sample_rate as f32).sin())
+///     .collect();
+///
+/// let converter = AudioRumbleConverter::default();
+/// let mut stream = converter.stream(sample_rate, 1);
+/// let rumbles: Vec<Rumble> = samples
+///     .chunks(240)
+///     .flat_map(|chunk| stream.push(chunk))
+///     .collect();
+///
+/// // Same as converting the whole clip at once
+/// let track = converter.track(&AudioClip::from_samples(sample_rate, samples));
+/// let expected: Vec<Rumble> = track.frames().iter().map(|frame| frame.rumble).collect();
+/// assert_eq!(rumbles, expected);
+/// assert_eq!(stream.flush(), None);
+/// ```
+///
+/// [`Rumble`]: ../struct.Rumble.html
+/// [`JoyConDriver::rumble`]: ../trait.JoyConDriver.html#method.rumble
+/// [`JoyConDevice::rumble_paced`]: ../../device/struct.JoyConDevice.html#method.rumble_paced
+#[derive(Debug, Clone)]
+pub struct AudioRumbleStream {
+    converter: AudioRumbleConverter,
+    sample_rate: u32,
+    channels: u16,
+    /// Samples of an interleaved frame cut by the end of a chunk.
+    partial: Vec<f32>,
+    /// Mono samples of the frame being filled.
+    samples: Vec<f32>,
+}
+
+impl AudioRumbleStream {
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Duration of audio fed but not converted yet.
+    pub fn pending(&self) -> Duration {
+        if self.sample_rate == 0 {
+            return Duration::from_millis(0);
+        }
+
+        Duration::from_secs_f64(self.samples.len() as f64 / self.sample_rate as f64)
+    }
+
+    /// Feed interleaved samples in -1.0..=1.0, and returns rumble of the frames completed.
+    pub fn push(&mut self, samples: &[f32]) -> Vec<Rumble> {
+        // A stream without sample rate has no duration
+        if self.sample_rate == 0 {
+            return Vec::new();
+        }
+
+        let channels = self.channels as usize;
+        let frame_len = self.converter.frame_len(self.sample_rate);
+        let mut rumbles = Vec::new();
+
+        for &sample in samples {
+            self.partial.push(sample);
+            if self.partial.len() < channels {
+                continue;
+            }
+
+            let mono = self.partial.drain(..).sum::<f32>() / channels as f32;
+            self.samples.push(mono);
+            if self.samples.len() >= frame_len {
+                rumbles.push(
+                    self.converter
+                        .convert_frame(self.sample_rate, &self.samples),
+                );
+                self.samples.clear();
+            }
+        }
+
+        rumbles
+    }
+
+    /// Feed interleaved 16-bit samples. See [`push`].
+    ///
+    /// [`push`]: #method.push
+    pub fn push_i16(&mut self, samples: &[i16]) -> Vec<Rumble> {
+        let samples = samples
+            .iter()
+            .map(|&s| s as f32 / 32768.0)
+            .collect::<Vec<_>>();
+
+        self.push(&samples)
+    }
+
+    /// Convert the audio left shorter than a frame, e.g. at the end of the stream.
+    pub fn flush(&mut self) -> Option<Rumble> {
+        self.partial.clear();
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let rumble = self
+            .converter
+            .convert_frame(self.sample_rate, &self.samples);
+        self.samples.clear();
+        Some(rumble)
+    }
+}
+
+/// Amplitude of the frequency component by Goertzel algorithm with Hann window.
+/// A sine wave of amplitude 1.0 yields about 1.0.
+fn magnitude(sample_rate: u32, samples: &[f32], frequency: f32) -> f32 {
+    let n = samples.len();
+    if n < 2 {
+        return 0.0;
+    }
+
+    let coeff = 2.0 * (2.0 * std::f32::consts::PI * frequency / sample_rate as f32).cos();
+    let window =
+        |i: usize| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (n - 1) as f32).cos();
+
+    let (s1, s2) = samples
+        .iter()
+        .enumerate()
+        .fold((0.0f32, 0.0f32), |(s1, s2), (i, &x)| {
+            (x * window(i) + coeff * s1 - s2, s1)
+        });
+    let power = s1 * s1 + s2 * s2 - coeff * s1 * s2;
+
+    // Hann window halves the gain
+    power.max(0.0).sqrt() * 4.0 / n as f32
+}
+
+mod wav {
+    use super::*;
+
+    const FORMAT_PCM: u16 = 0x0001;
+    const FORMAT_IEEE_FLOAT: u16 = 0x0003;
+    const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+    struct Format {
+        format: u16,
+        channels: u16,
+        sample_rate: u32,
+        bits_per_sample: u16,
+    }
+
+    fn u16_at(bytes: &[u8], at: usize) -> Option<u16> {
+        bytes
+            .get(at..at + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> Option<u32> {
+        bytes
+            .get(at..at + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<AudioClip, AudioError> {
+        if bytes.get(0..4) != Some(b"RIFF") || bytes.get(8..12) != Some(b"WAVE") {
+            return Err(AudioError::InvalidWav);
+        }
+
+        let mut format = None;
+        let mut data = None;
+        let mut at = 12;
+        while let (Some(id), Some(len)) = (bytes.get(at..at + 4), u32_at(bytes, at + 4)) {
+            let body = at + 8;
+            let end = (body + len as usize).min(bytes.len());
+            let chunk = &bytes[body..end];
+
+            match id {
+                b"fmt " => {
+                    let mut tag = u16_at(chunk, 0).ok_or(AudioError::InvalidWav)?;
+                    if tag == FORMAT_EXTENSIBLE {
+                        // The sub format GUID begins with the format tag
+                        tag = u16_at(chunk, 24).ok_or(AudioError::InvalidWav)?;
+                    }
+                    format = Some(Format {
+                        format: tag,
+                        channels: u16_at(chunk, 2).ok_or(AudioError::InvalidWav)?,
+                        sample_rate: u32_at(chunk, 4).ok_or(AudioError::InvalidWav)?,
+                        bits_per_sample: u16_at(chunk, 14).ok_or(AudioError::InvalidWav)?,
+                    });
+                }
+                b"data" => data = Some(chunk),
+                _ => {}
+            }
+
+            // Chunks are padded to even length
+            at = body + len as usize + (len as usize & 1);
+        }
+
+        let format = format.ok_or(AudioError::InvalidWav)?;
+        let data = data.ok_or(AudioError::InvalidWav)?;
+        if format.channels == 0 || format.sample_rate == 0 {
+            return Err(AudioError::InvalidWav);
+        }
+
+        let samples = decode(&format, data)?;
+        Ok(AudioClip::from_interleaved(
+            format.sample_rate,
+            format.channels,
+            &samples,
+        ))
+    }
+
+    fn decode(format: &Format, data: &[u8]) -> Result<Vec<f32>, AudioError> {
+        let unsupported = AudioError::UnsupportedFormat {
+            format: format.format,
+            bits_per_sample: format.bits_per_sample,
+        };
+
+        let samples = match (format.format, format.bits_per_sample) {
+            (FORMAT_PCM, 8) => data.iter().map(|&b| (b as f32 - 128.0) / 128.0).collect(),
+            (FORMAT_PCM, 16) => data
+                .chunks_exact(2)
+                .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+                .collect(),
+            (FORMAT_PCM, 24) => data
+                .chunks_exact(3)
+                .map(|b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8388608.0)
+                .collect(),
+            (FORMAT_PCM, 32) => data
+                .chunks_exact(4)
+                .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2147483648.0)
+                .collect(),
+            (FORMAT_IEEE_FLOAT, 32) => data
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+            _ => return Err(unsupported),
+        };
+
+        Ok(samples)
+    }
+}
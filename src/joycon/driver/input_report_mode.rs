@@ -13,6 +13,9 @@
 //!
 //! If you want to implement input report mode, you can use [`InputReportMode<D>`].
 //!
+//! To avoid copies and allocations, read standard input reports into your buffer with
+//! [`read_into`], and decode fields when needed through [`ReportView`].
+//!
 //! [`SimpleHIDMode<D>`]: simple_hid_mode/struct.SimpleHIDMode.html
 //! [8 directions]: simple_hid_mode/enum.StickDirection.html
 //! [`StandardFullMode<D>`]: standard_full_mode/struct.StandardFullMode.html
@@ -22,8 +25,13 @@
 //! [button status]: struct.PushedButtons.html
 //! [analog stick data]: struct.AnalogStickData.html
 //! [`InputReportMode<D>`]: trait.InputReportMode.html
+//! [`read_into`]: standard_full_mode/struct.StandardFullMode.html#method.read_into
+//! [`ReportView`]: report_view/struct.ReportView.html
 
-pub use self::{simple_hid_mode::SimpleHIDMode, standard_full_mode::StandardFullMode};
+pub use self::{
+    report_view::ReportView, simple_hid_mode::SimpleHIDMode, standard_full_mode::StandardFullMode,
+    sub_command_mode::SubCommandMode,
+};
 use super::*;
 pub use common::*;
 use std::convert::TryFrom;
@@ -107,7 +115,12 @@ mod common {
         }
     }
 
-    /// Button status
+    /// Button status as lists of buttons.
+    ///
+    /// SL and SR are told as `Buttons::SL` and `Buttons::SR` on either side.
+    /// [`ButtonSet`] tells them apart, and doesn't allocate.
+    ///
+    /// [`ButtonSet`]: struct.ButtonSet.html
     #[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
     #[derive(Debug, Clone, Hash, Eq, PartialEq)]
     pub struct PushedButtons {
@@ -117,87 +130,362 @@ mod common {
     }
 
     impl PushedButtons {
-        const RIGHT_BUTTONS: [Buttons; 8] = [
-            Buttons::Y,
-            Buttons::X,
-            Buttons::B,
-            Buttons::A,
-            Buttons::SR,
-            Buttons::SL,
-            Buttons::R,
-            Buttons::ZR,
-        ];
-        const SHARED_BUTTONS: [Buttons; 8] = [
-            Buttons::Minus,
-            Buttons::Plus,
-            Buttons::RStick,
-            Buttons::LStick,
-            Buttons::Home,
-            Buttons::Capture,
-            // originally none
-            Buttons::Capture,
-            Buttons::ChargingGrip,
-        ];
-        const LEFT_BUTTONS: [Buttons; 8] = [
-            Buttons::Down,
-            Buttons::Up,
-            Buttons::Right,
-            Buttons::Left,
-            Buttons::SR,
-            Buttons::SL,
-            Buttons::L,
-            Buttons::ZL,
-        ];
-
+        /// Whether `button` is pushed. `Buttons::SL` and `Buttons::SR` are pushed on either side.
         pub fn contains(&self, button: Buttons) -> bool {
-            self.right.contains(&button)
-                || self.shared.contains(&button)
-                || self.left.contains(&button)
+            ButtonSet::from(self).contains(button)
         }
     }
 
     impl From<[u8; 3]> for PushedButtons {
         fn from(value: [u8; 3]) -> Self {
-            let right_val = value[0];
-            let shared_val = value[1];
-            let left_val = value[2];
+            PushedButtons::from(ButtonSet::from_raw(value))
+        }
+    }
 
-            let right = PushedButtons::RIGHT_BUTTONS
-                .iter()
-                .enumerate()
-                .filter(|(idx, _)| {
-                    let idx = 2u8.pow(*idx as u32) as u8;
-                    right_val & idx == idx
-                })
-                .map(|(_, b)| *b)
-                .collect();
-            let shared = PushedButtons::SHARED_BUTTONS
-                .iter()
-                .enumerate()
-                .filter(|(idx, _)| {
-                    let idx = 2u8.pow(*idx as u32) as u8;
-                    shared_val & idx == idx
-                })
-                .map(|(_, b)| *b)
-                .collect();
-            let left = PushedButtons::LEFT_BUTTONS
-                .iter()
-                .enumerate()
-                .filter(|(idx, _)| {
-                    let idx = 2u8.pow(*idx as u32) as u8;
-                    left_val & idx == idx
-                })
-                .map(|(_, b)| *b)
-                .collect();
+    impl From<ButtonSet> for PushedButtons {
+        fn from(set: ButtonSet) -> Self {
+            let byte = |shift: u32| {
+                ButtonSet::from_bits_truncate(set.bits() & (0xFF << shift))
+                    .iter()
+                    .map(|button| match button {
+                        Buttons::LeftSL | Buttons::RightSL => Buttons::SL,
+                        Buttons::LeftSR | Buttons::RightSR => Buttons::SR,
+                        button => button,
+                    })
+                    .collect()
+            };
 
             PushedButtons {
-                right,
-                shared,
-                left,
+                right: byte(0),
+                shared: byte(8),
+                left: byte(16),
             }
         }
     }
 
+    /// Button status in a bit field.
+    ///
+    /// Queries and set operations are O(1), and it's `Copy`.
+    /// Bits are laid out as in reports: the right byte, the shared byte, then the left byte.
+    ///
+    /// `Buttons::SL` and `Buttons::SR` stand for the button of either side,
+    /// and `Buttons::LeftSL` and so on for the button of one side.
+    ///
+    /// # Example
+    /// ```
+    /// use joycon_rs::prelude::*;
+    /// use joycon_rs::joycon::input_report_mode::ButtonSet;
+    ///
+    /// let pushed = ButtonSet::from_raw([0b0000_1000, 0b0000_0000, 0b0010_0000]);
+    /// assert!(pushed.contains(Buttons::A));
+    /// assert!(pushed.contains(Buttons::LeftSL));
+    /// assert!(pushed.contains(Buttons::SL));
+    /// assert!(!pushed.contains(Buttons::RightSL));
+    ///
+    /// let chord: ButtonSet = vec![Buttons::A, Buttons::LeftSL].into_iter().collect();
+    /// assert!(pushed.contains_all(chord));
+    /// assert_eq!(pushed - ButtonSet::from(Buttons::A), ButtonSet::from(Buttons::LeftSL));
+    /// assert_eq!(pushed.iter().collect::<Vec<_>>(), vec![Buttons::A, Buttons::LeftSL]);
+    /// ```
+    #[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
+    #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Default)]
+    pub struct ButtonSet(u32);
+
+    impl ButtonSet {
+        pub const EMPTY: ButtonSet = ButtonSet(0);
+        /// Every button. Bit 6 of the shared byte isn't a button.
+        pub const ALL: ButtonSet = ButtonSet(0x00FF_BFFF);
+
+        const BUTTONS: [Option<Buttons>; 24] = [
+            Some(Buttons::Y),
+            Some(Buttons::X),
+            Some(Buttons::B),
+            Some(Buttons::A),
+            Some(Buttons::RightSR),
+            Some(Buttons::RightSL),
+            Some(Buttons::R),
+            Some(Buttons::ZR),
+            Some(Buttons::Minus),
+            Some(Buttons::Plus),
+            Some(Buttons::RStick),
+            Some(Buttons::LStick),
+            Some(Buttons::Home),
+            Some(Buttons::Capture),
+            None,
+            Some(Buttons::ChargingGrip),
+            Some(Buttons::Down),
+            Some(Buttons::Up),
+            Some(Buttons::Right),
+            Some(Buttons::Left),
+            Some(Buttons::LeftSR),
+            Some(Buttons::LeftSL),
+            Some(Buttons::L),
+            Some(Buttons::ZL),
+        ];
+
+        /// From the 3 bytes of button status in reports.
+        pub const fn from_raw(value: [u8; 3]) -> Self {
+            let bits = value[0] as u32 | (value[1] as u32) << 8 | (value[2] as u32) << 16;
+            ButtonSet::from_bits_truncate(bits)
+        }
+
+        /// Into the 3 bytes of button status in reports.
+        pub const fn to_raw(self) -> [u8; 3] {
+            [self.0 as u8, (self.0 >> 8) as u8, (self.0 >> 16) as u8]
+        }
+
+        pub const fn bits(self) -> u32 {
+            self.0
+        }
+
+        /// Bits which aren't buttons are dropped.
+        pub const fn from_bits_truncate(bits: u32) -> Self {
+            ButtonSet(bits & ButtonSet::ALL.0)
+        }
+
+        /// Bits of `button`. `Buttons::SL` and `Buttons::SR` have the bits of both sides.
+        pub const fn of(button: Buttons) -> Self {
+            let bits = match button {
+                Buttons::Y => 1 << 0,
+                Buttons::X => 1 << 1,
+                Buttons::B => 1 << 2,
+                Buttons::A => 1 << 3,
+                Buttons::RightSR => 1 << 4,
+                Buttons::RightSL => 1 << 5,
+                Buttons::R => 1 << 6,
+                Buttons::ZR => 1 << 7,
+                Buttons::Minus => 1 << 8,
+                Buttons::Plus => 1 << 9,
+                Buttons::RStick => 1 << 10,
+                Buttons::LStick => 1 << 11,
+                Buttons::Home => 1 << 12,
+                Buttons::Capture => 1 << 13,
+                Buttons::ChargingGrip => 1 << 15,
+                Buttons::Down => 1 << 16,
+                Buttons::Up => 1 << 17,
+                Buttons::Right => 1 << 18,
+                Buttons::Left => 1 << 19,
+                Buttons::LeftSR => 1 << 20,
+                Buttons::LeftSL => 1 << 21,
+                Buttons::L => 1 << 22,
+                Buttons::ZL => 1 << 23,
+                Buttons::SR => 1 << 4 | 1 << 20,
+                Buttons::SL => 1 << 5 | 1 << 21,
+            };
+            ButtonSet(bits)
+        }
+
+        pub const fn is_empty(self) -> bool {
+            self.0 == 0
+        }
+
+        /// Number of buttons pushed.
+        pub const fn len(self) -> usize {
+            self.0.count_ones() as usize
+        }
+
+        /// Whether `button` is pushed. `Buttons::SL` and `Buttons::SR` are pushed on either side.
+        pub const fn contains(self, button: Buttons) -> bool {
+            self.intersects(ButtonSet::of(button))
+        }
+
+        /// Whether all of `other` are pushed.
+        pub const fn contains_all(self, other: ButtonSet) -> bool {
+            self.0 & other.0 == other.0
+        }
+
+        /// Whether any of `other` are pushed.
+        pub const fn intersects(self, other: ButtonSet) -> bool {
+            self.0 & other.0 != 0
+        }
+
+        pub fn insert(&mut self, button: Buttons) {
+            self.0 |= ButtonSet::of(button).0;
+        }
+
+        pub fn remove(&mut self, button: Buttons) {
+            self.0 &= !ButtonSet::of(button).0;
+        }
+
+        pub const fn union(self, other: ButtonSet) -> Self {
+            ButtonSet(self.0 | other.0)
+        }
+
+        pub const fn intersection(self, other: ButtonSet) -> Self {
+            ButtonSet(self.0 & other.0)
+        }
+
+        pub const fn difference(self, other: ButtonSet) -> Self {
+            ButtonSet(self.0 & !other.0)
+        }
+
+        pub const fn symmetric_difference(self, other: ButtonSet) -> Self {
+            ButtonSet(self.0 ^ other.0)
+        }
+
+        /// Buttons other than `self`.
+        pub const fn complement(self) -> Self {
+            ButtonSet(!self.0 & ButtonSet::ALL.0)
+        }
+
+        /// Pushed buttons in order of bits. SL and SR are told with their sides.
+        pub fn iter(self) -> ButtonSetIter {
+            ButtonSetIter(self.0)
+        }
+    }
+
+    /// Iterator over buttons of [`ButtonSet`].
+    ///
+    /// [`ButtonSet`]: struct.ButtonSet.html
+    #[derive(Debug, Clone)]
+    pub struct ButtonSetIter(u32);
+
+    impl Iterator for ButtonSetIter {
+        type Item = Buttons;
+
+        fn next(&mut self) -> Option<Buttons> {
+            while self.0 != 0 {
+                let bit = self.0.trailing_zeros();
+                self.0 &= self.0 - 1;
+                if let Some(button) = ButtonSet::BUTTONS[bit as usize] {
+                    return Some(button);
+                }
+            }
+            None
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let len = self.0.count_ones() as usize;
+            (len, Some(len))
+        }
+    }
+
+    impl ExactSizeIterator for ButtonSetIter {}
+
+    impl IntoIterator for ButtonSet {
+        type Item = Buttons;
+        type IntoIter = ButtonSetIter;
+
+        fn into_iter(self) -> ButtonSetIter {
+            self.iter()
+        }
+    }
+
+    impl std::iter::FromIterator<Buttons> for ButtonSet {
+        fn from_iter<I: IntoIterator<Item = Buttons>>(iter: I) -> Self {
+            let mut set = ButtonSet::EMPTY;
+            set.extend(iter);
+            set
+        }
+    }
+
+    impl Extend<Buttons> for ButtonSet {
+        fn extend<I: IntoIterator<Item = Buttons>>(&mut self, iter: I) {
+            iter.into_iter().for_each(|button| self.insert(button));
+        }
+    }
+
+    impl From<Buttons> for ButtonSet {
+        fn from(button: Buttons) -> Self {
+            ButtonSet::of(button)
+        }
+    }
+
+    impl From<[u8; 3]> for ButtonSet {
+        fn from(value: [u8; 3]) -> Self {
+            ButtonSet::from_raw(value)
+        }
+    }
+
+    impl From<&PushedButtons> for ButtonSet {
+        fn from(pushed: &PushedButtons) -> Self {
+            let side = |buttons: &[Buttons], sl: Buttons, sr: Buttons| -> ButtonSet {
+                buttons
+                    .iter()
+                    .map(|&button| match button {
+                        Buttons::SL => sl,
+                        Buttons::SR => sr,
+                        button => button,
+                    })
+                    .collect()
+            };
+
+            side(&pushed.right, Buttons::RightSL, Buttons::RightSR)
+                | pushed.shared.iter().copied().collect()
+                | side(&pushed.left, Buttons::LeftSL, Buttons::LeftSR)
+        }
+    }
+
+    impl From<PushedButtons> for ButtonSet {
+        fn from(pushed: PushedButtons) -> Self {
+            ButtonSet::from(&pushed)
+        }
+    }
+
+    impl std::ops::BitOr for ButtonSet {
+        type Output = ButtonSet;
+
+        fn bitor(self, rhs: ButtonSet) -> ButtonSet {
+            self.union(rhs)
+        }
+    }
+
+    impl std::ops::BitAnd for ButtonSet {
+        type Output = ButtonSet;
+
+        fn bitand(self, rhs: ButtonSet) -> ButtonSet {
+            self.intersection(rhs)
+        }
+    }
+
+    impl std::ops::BitXor for ButtonSet {
+        type Output = ButtonSet;
+
+        fn bitxor(self, rhs: ButtonSet) -> ButtonSet {
+            self.symmetric_difference(rhs)
+        }
+    }
+
+    impl std::ops::Sub for ButtonSet {
+        type Output = ButtonSet;
+
+        fn sub(self, rhs: ButtonSet) -> ButtonSet {
+            self.difference(rhs)
+        }
+    }
+
+    impl std::ops::Not for ButtonSet {
+        type Output = ButtonSet;
+
+        fn not(self) -> ButtonSet {
+            self.complement()
+        }
+    }
+
+    impl std::ops::BitOrAssign for ButtonSet {
+        fn bitor_assign(&mut self, rhs: ButtonSet) {
+            *self = *self | rhs;
+        }
+    }
+
+    impl std::ops::BitAndAssign for ButtonSet {
+        fn bitand_assign(&mut self, rhs: ButtonSet) {
+            *self = *self & rhs;
+        }
+    }
+
+    impl std::ops::BitXorAssign for ButtonSet {
+        fn bitxor_assign(&mut self, rhs: ButtonSet) {
+            *self = *self ^ rhs;
+        }
+    }
+
+    impl std::ops::SubAssign for ButtonSet {
+        fn sub_assign(&mut self, rhs: ButtonSet) {
+            *self = *self - rhs;
+        }
+    }
+
     /// Analog stick data
     #[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
     #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -232,6 +520,13 @@ mod common {
         pub vibrator_input_report: u8,
     }
 
+    impl CommonReport {
+        /// Pushed buttons in a bit field, which tells SL and SR with their sides.
+        pub fn buttons(&self) -> ButtonSet {
+            ButtonSet::from(&self.pushed_buttons)
+        }
+    }
+
     impl TryFrom<[u8; 13]> for CommonReport {
         type Error = JoyConError;
 
@@ -250,10 +545,7 @@ mod common {
                 )
             };
 
-            let pushed_buttons = {
-                let array = [report[3], report[4], report[5]];
-                PushedButtons::from(array)
-            };
+            let pushed_buttons = PushedButtons::from([report[3], report[4], report[5]]);
 
             let left_analog_stick_data = {
                 let array = [report[6], report[7], report[8]];
@@ -401,6 +693,9 @@ impl<EX> Eq for StandardInputReport<EX> where EX: TryFrom<[u8; 349], Error = Joy
 /// [`SubCommandReplyData`]: trait.SubCommandReplyData.html
 pub mod sub_command_mode {
     use super::*;
+    use std::cell::Cell;
+    use std::marker::PhantomData;
+    use std::time::{Duration, Instant};
 
     /// Ack byte. If it is ACK, it contains data type.
     #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
@@ -421,6 +716,158 @@ pub mod sub_command_mode {
         }
     }
 
+    /// Matches input reports with the reply to a sub-command, by sub-command id and deadline.
+    ///
+    /// Input reports that are not the reply are kept to be forwarded to the report consumer.
+    ///
+    /// # Example
+    /// ```
+    /// use joycon_rs::prelude::*;
+    /// use joycon_rs::joycon::input_report_mode::sub_command_mode::{SubCommandCorrelator, Correlation};
+    /// use std::time::Duration;
+    ///
+    /// let mut correlator = SubCommandCorrelator::new(
+    ///     SubCommand::SetPlayerLights as u8,
+    ///     Duration::from_millis(100),
+    /// );
+    ///
+    /// // Standard full report arrives
+    /// let mut report = [0u8; 362];
+    /// report[0] = 0x30;
+    /// assert_eq!(correlator.accept(report), Correlation::Forwarded);
+    ///
+    /// // Reply to another sub-command arrives
+    /// report[0] = 0x21;
+    /// report[13] = 0x80;
+    /// report[14] = SubCommand::GetPlayerLights as u8;
+    /// assert_eq!(correlator.accept(report), Correlation::Forwarded);
+    ///
+    /// // Reply to the sub-command arrives
+    /// report[14] = SubCommand::SetPlayerLights as u8;
+    /// assert_eq!(correlator.accept(report), Correlation::Ack(report));
+    ///
+    /// assert_eq!(correlator.into_forwarded().len(), 2);
+    /// ```
+    #[derive(Debug, Clone)]
+    pub struct SubCommandCorrelator {
+        sub_command: u8,
+        deadline: Instant,
+        forwarded: Vec<[u8; 362]>,
+    }
+
+    /// Result of matching an input report with the reply to a sub-command.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Correlation {
+        /// The reply with ACK.
+        Ack([u8; 362]),
+        /// The reply with NACK.
+        Nack([u8; 362]),
+        /// Not the reply. The report is kept to be forwarded.
+        Forwarded,
+    }
+
+    impl SubCommandCorrelator {
+        /// Input report ID of sub-command replies.
+        pub const REPLY_REPORT_ID: u8 = 0x21;
+        /// Longest read while waiting for the reply.
+        const READ_STEP: Duration = Duration::from_millis(50);
+
+        /// Start waiting for the reply to `sub_command` until `timeout` passes.
+        pub fn new(sub_command: u8, timeout: Duration) -> Self {
+            SubCommandCorrelator {
+                sub_command,
+                deadline: Instant::now() + timeout,
+                forwarded: Vec::new(),
+            }
+        }
+
+        /// Sub-command waiting for the reply.
+        pub fn sub_command(&self) -> u8 {
+            self.sub_command
+        }
+
+        /// Time left until the deadline. `None` if the deadline has passed.
+        pub fn remaining(&self) -> Option<Duration> {
+            let now = Instant::now();
+            if now < self.deadline {
+                Some(self.deadline - now)
+            } else {
+                None
+            }
+        }
+
+        /// Match an input report with the reply.
+        pub fn accept(&mut self, report: [u8; 362]) -> Correlation {
+            if report[0] == Self::REPLY_REPORT_ID && report[14] == self.sub_command {
+                match AckByte::from(report[13]) {
+                    AckByte::Ack { .. } => Correlation::Ack(report),
+                    AckByte::Nack => Correlation::Nack(report),
+                }
+            } else {
+                self.forwarded.push(report);
+                Correlation::Forwarded
+            }
+        }
+
+        /// Read input reports through `driver` until the reply arrives or the deadline passes.
+        /// Returns `None` if the deadline has passed.
+        ///
+        /// Replies read by report consumers meanwhile are taken from the devices of `driver`
+        /// (see [`JoyConDriver::keep_reply`]).
+        ///
+        /// [`JoyConDriver::keep_reply`]: ../../trait.JoyConDriver.html#method.keep_reply
+        pub fn wait<D>(&mut self, driver: &D) -> JoyConResult<Option<Correlation>>
+        where
+            D: JoyConDriver + ?Sized,
+        {
+            while let Some(remaining) = self.remaining() {
+                if let Some(reply) = self.take_kept_reply(driver) {
+                    return Ok(Some(self.accept(reply)));
+                }
+
+                // Read in short steps, to notice replies kept by report consumers
+                let timeout = remaining.min(Self::READ_STEP);
+                let mut buf = [0u8; 362];
+                match driver.read_timeout(&mut buf, timeout.as_millis().max(1) as i32) {
+                    Ok(_) => {}
+                    // Nothing arrived before the timeout
+                    Err(JoyConError::JoyConReportError(JoyConReportError::EmptyReport)) => continue,
+                    Err(e) => return Err(e),
+                }
+
+                match self.accept(buf) {
+                    Correlation::Forwarded => continue,
+                    reply => return Ok(Some(reply)),
+                }
+            }
+
+            Ok(None)
+        }
+
+        fn take_kept_reply<D>(&self, driver: &D) -> Option<[u8; 362]>
+        where
+            D: JoyConDriver + ?Sized,
+        {
+            let reply = driver.devices().iter().find_map(|device| {
+                match device.lock() {
+                    Ok(d) => d,
+                    Err(e) => e.into_inner(),
+                }
+                .take_reply(self.sub_command)
+            })?;
+
+            let mut buf = [0u8; 362];
+            let len = reply.len().min(buf.len());
+            buf[..len].copy_from_slice(&reply[..len]);
+            Some(buf)
+        }
+
+        /// Input reports which are not the reply, in order of arrival.
+        pub fn into_forwarded(self) -> Vec<[u8; 362]> {
+            self.forwarded
+        }
+    }
+
     /// An interface for dealing with sub-command's reply.
     ///
     /// # Example - implement `SubCommandReplyData`
@@ -544,6 +991,165 @@ pub mod sub_command_mode {
             })
         }
     }
+
+    /// Joy-Con issuing a sub-command periodically and emitting its replies.
+    ///
+    /// Every time [`read_input_report`] is called, the sub-command `RD::SUB_COMMAND` is sent
+    /// if the interval has elapsed since the last one was sent.
+    /// Then input reports are read until the reply (input report `0x21`) to the sub-command arrives.
+    /// The other input reports mixed in are skipped.
+    /// If the reply does not arrive within the interval, the sub-command is sent again.
+    ///
+    /// The input report mode of the Joy-Con itself is left unchanged.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use joycon_rs::prelude::{*, lights::*};
+    /// use joycon_rs::joycon::input_report_mode::sub_command_mode::SubCommandMode;
+    ///
+    /// // Poll status of player lights
+    /// fn poll_lights(driver: SimpleJoyConDriver) -> JoyConResult<()> {
+    ///     let joycon = SubCommandMode::<_, LightsStatus>::new(driver)?;
+    ///
+    ///     loop {
+    ///         let report = joycon.read_input_report()?;
+    ///         dbg!(report.extra.reply);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`read_input_report`]: ../trait.InputReportMode.html#method.read_input_report
+    pub struct SubCommandMode<D, RD>
+    where
+        D: JoyConDriver,
+        RD: SubCommandReplyData,
+    {
+        driver: D,
+        interval: Duration,
+        last_sent: Cell<Option<Instant>>,
+        _phantom: PhantomData<RD>,
+    }
+
+    impl<D, RD> SubCommandMode<D, RD>
+    where
+        D: JoyConDriver,
+        RD: SubCommandReplyData,
+    {
+        /// Default interval of sending sub-command.
+        pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(100);
+
+        /// Constructs a new `SubCommandMode` sending sub-command at the specified interval.
+        pub fn with_interval(driver: D, interval: Duration) -> JoyConResult<Self> {
+            Ok(SubCommandMode {
+                driver,
+                interval,
+                last_sent: Cell::new(None),
+                _phantom: PhantomData,
+            })
+        }
+
+        /// Interval of sending sub-command.
+        pub fn interval(&self) -> Duration {
+            self.interval
+        }
+
+        /// Set interval of sending sub-command.
+        pub fn set_interval(&mut self, interval: Duration) {
+            self.interval = interval;
+        }
+
+        /// Send the sub-command if the interval has elapsed,
+        /// and returns the time until the next sending.
+        fn send_if_needed(&self) -> JoyConResult<Duration> {
+            let now = Instant::now();
+            if let Some(last_sent) = self.last_sent.get() {
+                let elapsed = now.duration_since(last_sent);
+                if elapsed < self.interval {
+                    return Ok(self.interval - elapsed);
+                }
+            }
+
+            self.driver.send_output_report(
+                Command::RumbleAndSubCommand as u8,
+                RD::SUB_COMMAND as u8,
+                RD::ARGS.as_ref(),
+            )?;
+            self.last_sent.set(Some(now));
+
+            Ok(self.interval)
+        }
+
+        /// Read input reports until the reply arrives or the deadline passes.
+        fn read_reply(
+            &self,
+            deadline: Option<Instant>,
+        ) -> JoyConResult<StandardInputReport<SubCommandReport<RD>>> {
+            loop {
+                let until_next_sending = self.send_if_needed()?;
+                let timeout = match deadline {
+                    Some(deadline) => {
+                        let now = Instant::now();
+                        if deadline <= now {
+                            return Err(JoyConReportError::EmptyReport.into());
+                        }
+                        until_next_sending.min(deadline - now)
+                    }
+                    None => until_next_sending,
+                };
+
+                let mut buf = [0u8; 362];
+                match self
+                    .driver
+                    .read_timeout(&mut buf, timeout.as_millis().max(1) as i32)
+                {
+                    Ok(_) => {}
+                    // Nothing arrived before the timeout
+                    Err(JoyConError::JoyConReportError(JoyConReportError::EmptyReport)) => continue,
+                    Err(e) => return Err(e),
+                }
+
+                if buf[0] == 0x21 && buf[14] == RD::SUB_COMMAND as u8 {
+                    return StandardInputReport::try_from(buf);
+                }
+            }
+        }
+    }
+
+    impl<D, RD> InputReportMode<D> for SubCommandMode<D, RD>
+    where
+        D: JoyConDriver,
+        RD: SubCommandReplyData + 'static + Send,
+    {
+        type Report = StandardInputReport<SubCommandReport<RD>>;
+        type ArgsType = RD::ArgsType;
+        const SUB_COMMAND: SubCommand = RD::SUB_COMMAND;
+        const ARGS: Self::ArgsType = RD::ARGS;
+
+        fn new(driver: D) -> JoyConResult<Self> {
+            Self::with_interval(driver, Self::DEFAULT_INTERVAL)
+        }
+
+        fn read_input_report(&self) -> JoyConResult<Self::Report> {
+            self.read_reply(None)
+        }
+
+        fn read_input_report_timeout(&self, timeout: i32) -> JoyConResult<Self::Report> {
+            let deadline = Instant::now() + Duration::from_millis(timeout.max(0) as u64);
+            self.read_reply(Some(deadline))
+        }
+
+        fn driver(&self) -> &D {
+            &self.driver
+        }
+
+        fn driver_mut(&mut self) -> &mut D {
+            &mut self.driver
+        }
+
+        fn into_driver(self) -> D {
+            self.driver
+        }
+    }
 }
 
 /// Receive standard full report (standard input report with IMU(6-Axis sensor) data).
@@ -551,6 +1157,7 @@ pub mod sub_command_mode {
 /// Pushes current state at 60Hz (ProCon: 120Hz).
 pub mod standard_full_mode {
     use super::*;
+    use std::time::{Duration, Instant};
 
     /// IMU(6-Axis sensor)'s value.
     #[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
@@ -674,6 +1281,61 @@ pub mod standard_full_mode {
         driver: D,
     }
 
+    impl<D> StandardFullMode<D>
+    where
+        D: JoyConDriver,
+    {
+        /// Read Joy-Con's input report into `buf`, and view it without copies.
+        /// `buf` should be 362 bytes long to hold the whole report.
+        ///
+        /// Sub-command replies are handed back like [`read_input_report`].
+        ///
+        /// [`read_input_report`]: ../trait.InputReportMode.html#method.read_input_report
+        pub fn read_into<'a>(&self, buf: &'a mut [u8]) -> JoyConResult<ReportView<'a>> {
+            let len = self.read_report(buf, None)?;
+
+            ReportView::new(&buf[..len])
+        }
+
+        /// * timeout - milli seconds
+        pub fn read_into_timeout<'a>(
+            &self,
+            buf: &'a mut [u8],
+            timeout: i32,
+        ) -> JoyConResult<ReportView<'a>> {
+            let len = self.read_report(buf, Some(timeout))?;
+
+            ReportView::new(&buf[..len])
+        }
+
+        /// Read a report other than sub-command replies into `buf`, and return its length.
+        /// Without `timeout`, it blocks.
+        fn read_report(&self, buf: &mut [u8], timeout: Option<i32>) -> JoyConResult<usize> {
+            let deadline =
+                Instant::now() + Duration::from_millis(timeout.unwrap_or(0).max(0) as u64);
+
+            loop {
+                let len = match timeout {
+                    None => self.driver.read(buf)?,
+                    // Negative timeout blocks
+                    Some(timeout) if timeout < 0 => self.driver.read_timeout(buf, timeout)?,
+                    Some(_) => {
+                        let remaining = deadline.saturating_duration_since(Instant::now());
+                        self.driver
+                            .read_timeout(buf, remaining.as_millis() as i32)?
+                    }
+                };
+
+                let report = &buf[..len];
+                if report.first() != Some(&sub_command_mode::SubCommandCorrelator::REPLY_REPORT_ID)
+                {
+                    return Ok(len);
+                }
+                self.driver.keep_reply(report);
+            }
+        }
+    }
+
     impl<D> InputReportMode<D> for StandardFullMode<D>
     where
         D: JoyConDriver,
@@ -694,13 +1356,29 @@ pub mod standard_full_mode {
                 driver.enable_feature(JoyConFeature::IMUFeature(IMUConfig::default()))?;
             }
 
-            driver.set_valid_reply(false);
-
             driver.send_sub_command(Self::SUB_COMMAND, Self::ARGS.as_ref())?;
 
             Ok(StandardFullMode { driver })
         }
 
+        /// Sub-command replies are handed back to the sender waiting for them,
+        /// and the next input report is read instead.
+        fn read_input_report(&self) -> JoyConResult<Self::Report> {
+            let mut buf = [0u8; 362];
+            self.read_report(&mut buf, None)?;
+
+            Self::Report::try_from(buf)
+        }
+
+        /// Sub-command replies are handed back to the sender waiting for them,
+        /// and the next input report is read instead within `timeout`.
+        fn read_input_report_timeout(&self, timeout: i32) -> JoyConResult<Self::Report> {
+            let mut buf = [0u8; 362];
+            self.read_report(&mut buf, Some(timeout))?;
+
+            Self::Report::try_from(buf)
+        }
+
         fn driver(&self) -> &D {
             &self.driver
         }
@@ -817,7 +1495,7 @@ pub mod simple_hid_mode {
                             .iter()
                             .enumerate()
                             .filter(|(idx, _)| {
-                                let idx = 2u8.pow(*idx as u32) as u8;
+                                let idx = 2u8.pow(*idx as u32);
                                 byte_1 & idx == idx
                             })
                             .map(|(_, b)| b),
@@ -827,7 +1505,7 @@ pub mod simple_hid_mode {
                             .iter()
                             .enumerate()
                             .filter(|(idx, _)| {
-                                let idx = 2u8.pow(*idx as u32) as u8;
+                                let idx = 2u8.pow(*idx as u32);
                                 byte_2 & idx == idx
                             })
                             .map(|(_, b)| b),
@@ -938,3 +1616,153 @@ pub mod simple_hid_mode {
         }
     }
 }
+
+/// Views over standard input reports in a buffer, without copies and allocations.
+///
+/// Read reports with [`StandardFullMode::read_into`], and fields are decoded when asked.
+///
+/// # Example
+/// ```no_run
+/// use joycon_rs::prelude::*;
+///
+/// fn print_a(standard_full_mode: &StandardFullMode<SimpleJoyConDriver>) -> JoyConResult<()> {
+///     // One buffer for every report
+///     let mut buf = [0u8; 362];
+///
+///     loop {
+///         let report = standard_full_mode.read_into(&mut buf)?;
+///
+///         if report.buttons().contains(Buttons::A) {
+///             println!("A pushed at {}: {:?}", report.timer(), report.imu_frame(0));
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`StandardFullMode::read_into`]: ../standard_full_mode/struct.StandardFullMode.html#method.read_into
+pub mod report_view {
+    use super::standard_full_mode::{AxisData, IMUData};
+    use super::*;
+
+    /// Borrowed standard input report.
+    ///
+    /// # Example
+    /// ```
+    /// use joycon_rs::prelude::*;
+    /// use joycon_rs::joycon::input_report_mode::{BatteryLevel, ReportView};
+    ///
+    /// let mut buf = [0u8; 362];
+    /// buf[0] = 0x30; // standard full mode
+    /// buf[1] = 42; // timer
+    /// buf[2] = 0x8E; // full battery, Joy-Con
+    /// buf[3] = 0b0000_1000; // A
+    /// buf[13..15].copy_from_slice(&4096i16.to_le_bytes()); // accelerometer X of the latest frame
+    ///
+    /// let report = ReportView::new(&buf).unwrap();
+    /// assert_eq!(report.timer(), 42);
+    /// assert_eq!(report.battery().unwrap().level, BatteryLevel::Full);
+    /// assert!(report.buttons().contains(Buttons::A));
+    /// assert_eq!(report.imu_frame(0).unwrap().accel_x, 4096);
+    /// assert_eq!(report.imu_frame(3), None);
+    /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct ReportView<'a> {
+        buf: &'a [u8],
+    }
+
+    impl<'a> ReportView<'a> {
+        /// Length of the common part.
+        const COMMON_LEN: usize = 13;
+        /// Length of each IMU frame.
+        const FRAME_LEN: usize = 12;
+        /// Input report IDs of reports with IMU data.
+        const IMU_REPORT_IDS: [u8; 4] = [0x30, 0x31, 0x32, 0x33];
+
+        /// View over a report in `buf`. `buf` must be as long as the common part of standard input reports.
+        pub fn new(buf: &'a [u8]) -> JoyConResult<Self> {
+            match buf.len() {
+                0 => Err(JoyConReportError::EmptyReport.into()),
+                len if len < Self::COMMON_LEN => {
+                    Err(InvalidStandardInputReport::ShortReport(len).into())
+                }
+                _ => Ok(ReportView { buf }),
+            }
+        }
+
+        /// The whole report.
+        pub fn as_bytes(&self) -> &'a [u8] {
+            self.buf
+        }
+
+        pub fn input_report_id(&self) -> u8 {
+            self.buf[0]
+        }
+
+        pub fn timer(&self) -> u8 {
+            self.buf[1]
+        }
+
+        pub fn battery(&self) -> JoyConResult<Battery> {
+            Battery::try_from(self.buf[2] >> 4)
+        }
+
+        pub fn connection_info(&self) -> JoyConResult<ConnectionInfo> {
+            ConnectionInfo::try_from(self.buf[2] & 0xF)
+        }
+
+        pub fn buttons(&self) -> ButtonSet {
+            ButtonSet::from_raw([self.buf[3], self.buf[4], self.buf[5]])
+        }
+
+        pub fn left_analog_stick_data(&self) -> AnalogStickData {
+            AnalogStickData::from([self.buf[6], self.buf[7], self.buf[8]])
+        }
+
+        pub fn right_analog_stick_data(&self) -> AnalogStickData {
+            AnalogStickData::from([self.buf[9], self.buf[10], self.buf[11]])
+        }
+
+        pub fn vibrator_input_report(&self) -> u8 {
+            self.buf[12]
+        }
+
+        /// Bytes after the common part, e.g. IMU data or a reply to a sub-command.
+        pub fn extra(&self) -> &'a [u8] {
+            &self.buf[Self::COMMON_LEN..]
+        }
+
+        /// Whether the report has IMU data.
+        pub fn has_imu_data(&self) -> bool {
+            Self::IMU_REPORT_IDS.contains(&self.input_report_id())
+        }
+
+        /// An IMU frame. 0 is the latest, and 2 is 10ms older.
+        /// `None` if the report doesn't have it.
+        pub fn imu_frame(&self, index: usize) -> Option<AxisData> {
+            if !self.has_imu_data() || index >= 3 {
+                return None;
+            }
+
+            let start = Self::COMMON_LEN + Self::FRAME_LEN * index;
+            let bytes = self.buf.get(start..start + Self::FRAME_LEN)?;
+            let mut frame = [0u8; 12];
+            frame.copy_from_slice(bytes);
+
+            Some(AxisData::from(frame))
+        }
+
+        /// All 3 IMU frames. `None` if the report doesn't have them.
+        pub fn imu_data(&self) -> Option<IMUData> {
+            Some(IMUData {
+                data: [self.imu_frame(0)?, self.imu_frame(1)?, self.imu_frame(2)?],
+            })
+        }
+
+        /// Decode the common part.
+        pub fn common(&self) -> JoyConResult<CommonReport> {
+            let mut report = [0u8; 13];
+            report.copy_from_slice(&self.buf[..Self::COMMON_LEN]);
+            CommonReport::try_from(report)
+        }
+    }
+}
@@ -20,6 +20,7 @@ pub mod imu_sensitivity {
         PM2000dps = 0x03,
     }
 
+    #[allow(clippy::derivable_impls)]
     impl Default for GyroscopeSensitivity {
         fn default() -> Self {
             GyroscopeSensitivity::PM2000dps
@@ -35,6 +36,7 @@ pub mod imu_sensitivity {
         PM16G = 0x03,
     }
 
+    #[allow(clippy::derivable_impls)]
     impl Default for AccelerometerSensitivity {
         fn default() -> Self {
             AccelerometerSensitivity::PM8G
@@ -48,6 +50,7 @@ pub mod imu_sensitivity {
         F208Hz = 0x01,
     }
 
+    #[allow(clippy::derivable_impls)]
     impl Default for GyroscopePerformanceRate {
         fn default() -> Self {
             GyroscopePerformanceRate::F208Hz
@@ -61,6 +64,7 @@ pub mod imu_sensitivity {
         F100Hz = 0x01,
     }
 
+    #[allow(clippy::derivable_impls)]
     impl Default for AccelerometerAntiAliasingFilterBandwidth {
         fn default() -> Self {
             AccelerometerAntiAliasingFilterBandwidth::F100Hz
@@ -95,7 +99,7 @@ pub mod imu_sensitivity {
         }
     }
 
-    #[allow(clippy::derive_hash_xor_eq)]
+    #[allow(clippy::derived_hash_with_manual_eq)]
     impl Hash for IMUConfig {
         fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
             // Returns constant value.
@@ -64,6 +64,18 @@ pub struct LightsStatus {
 const LIGHT_UP: [LightUp; 4] = [LightUp::LED0, LightUp::LED1, LightUp::LED2, LightUp::LED3];
 const FLASH: [Flash; 4] = [Flash::LED0, Flash::LED1, Flash::LED2, Flash::LED3];
 
+/// Player lights of each player slot (0 - 7), as Nintendo Switch shows.
+pub const PLAYER_LIGHTS: [&[LightUp]; 8] = [
+    &[LightUp::LED0],
+    &[LightUp::LED0, LightUp::LED1],
+    &[LightUp::LED0, LightUp::LED1, LightUp::LED2],
+    &[LightUp::LED0, LightUp::LED1, LightUp::LED2, LightUp::LED3],
+    &[LightUp::LED0, LightUp::LED3],
+    &[LightUp::LED0, LightUp::LED2],
+    &[LightUp::LED0, LightUp::LED2, LightUp::LED3],
+    &[LightUp::LED1, LightUp::LED2],
+];
+
 impl TryFrom<[u8; 35]> for LightsStatus {
     type Error = JoyConError;
 
@@ -100,7 +112,10 @@ impl SubCommandReplyData for LightsStatus {
 
 pub mod home_button {
     use super::*;
+    use crate::result::InvalidHomeLightPattern;
+    use std::time::Duration;
 
+    /// 4-bit unsigned integer. Converting from `u8` saturates at 15.
     #[allow(non_camel_case_types)]
     #[derive(Copy, Clone, Debug, Hash, Ord, PartialOrd, Eq, PartialEq)]
     pub struct u4(u8);
@@ -127,6 +142,53 @@ pub mod home_button {
         }
     }
 
+    /// Max number of phases in a pattern.
+    pub const MAX_PHASES: usize = 15;
+    /// Max global mini cycle duration (ms).
+    pub const MAX_GLOBAL_MINI_CYCLE_DURATION: u8 = 175;
+    /// Min global mini cycle duration (ms) except 0 (off).
+    pub const MIN_GLOBAL_MINI_CYCLE_DURATION: u8 = 8;
+
+    /// Encode global mini cycle duration (ms), saturating in 8ms - 175ms except 0.
+    fn encode_global_mini_cycle_duration(ms: u8) -> u4 {
+        if ms == 0 {
+            0.into()
+        } else {
+            ((ms.clamp(
+                MIN_GLOBAL_MINI_CYCLE_DURATION,
+                MAX_GLOBAL_MINI_CYCLE_DURATION,
+            ) - 7)
+                / 12
+                + 1)
+            .into()
+        }
+    }
+
+    /// Decode global mini cycle duration into ms.
+    fn decode_global_mini_cycle_duration(code: u4) -> u16 {
+        match u8::from(code) {
+            0 => 0,
+            code => code as u16 * 12 - 4,
+        }
+    }
+
+    /// Encode intensity (%), saturating in 0% - 100%.
+    fn encode_intensity(percent: u8) -> u4 {
+        ((percent.min(100) as f32 / 6.25) as u8).into()
+    }
+
+    fn check_range(
+        field: &'static str,
+        value: u32,
+        range: std::ops::RangeInclusive<u32>,
+    ) -> Result<(), InvalidHomeLightPattern> {
+        if range.contains(&value) {
+            Ok(())
+        } else {
+            Err(InvalidHomeLightPattern::OutOfRange { field, value })
+        }
+    }
+
     /// Element of HOME light emitting pattern.
     /// The LED Duration Multiplier and the Fading Multiplier use the same algorithm:
     /// Global Mini Cycle Duration ms * Multiplier value.
@@ -144,6 +206,33 @@ pub mod home_button {
     }
 
     /// HOME light emitting pattern.
+    ///
+    /// Patterns are decoded from `[u8; 25]` as well as encoded into it.
+    /// [`try_new`] and [`try_add_phase`] report arguments the pattern cannot hold,
+    /// while [`new`] and [`add_phase`] saturate them.
+    ///
+    /// [`try_new`]: #method.try_new
+    /// [`try_add_phase`]: #method.try_add_phase
+    /// [`new`]: #method.new
+    /// [`add_phase`]: #method.add_phase
+    ///
+    /// # Example
+    /// ```
+    /// use joycon_rs::prelude::lights::home_button::*;
+    ///
+    /// let pattern = LightEmittingPattern::try_new(100, 0, 0)
+    ///     .and_then(|p| p.try_add_phase(100, 500, 0))
+    ///     .and_then(|p| p.try_add_phase(0, 500, 0))
+    ///     .unwrap();
+    ///
+    /// let data: [u8; 25] = pattern.clone().into();
+    /// assert_eq!(LightEmittingPattern::from(data), pattern);
+    ///
+    /// // Durations must be multiples of the global mini cycle duration up to 15 times
+    /// assert!(LightEmittingPattern::try_new(100, 0, 0).unwrap().try_add_phase(100, 2000, 0).is_err());
+    /// // Without the global mini cycle duration, fading takes no time
+    /// assert!(LightEmittingPattern::try_new(0, 0, 0).unwrap().try_add_phase(100, 500, 0).is_err());
+    /// ```
     #[derive(Clone, Debug, Hash, Eq, PartialEq)]
     pub struct LightEmittingPattern {
         phases_len: Option<u4>,
@@ -157,8 +246,10 @@ pub mod home_button {
 
     impl LightEmittingPattern {
         /// Constructor of `LightEmittingPattern`.
+        /// If arguments not in line with constraints, args will be saturated.
+        /// See [`try_new`](#method.try_new) to validate them.
         ///
-        /// * global_mini_cycle_duration (*ms*) - 0 <= global_mini_cycle_duration <= 175
+        /// * global_mini_cycle_duration (*ms*) - 0 or 8 <= global_mini_cycle_duration <= 175
         /// * led_start_intensity (*%*) - 0 <= led_start_intensity <= 100
         /// * repeat_count - 0 <= repeat_count <= 15: Value `0` is repeat forever.
         pub fn new(
@@ -166,30 +257,46 @@ pub mod home_button {
             led_start_intensity: u8,
             repeat_count: u4,
         ) -> Self {
-            let global_mini_cycle_duration = if global_mini_cycle_duration == 0 {
-                0.into()
-            } else {
-                ((global_mini_cycle_duration - 7) / 12 + 1).into()
-            };
-
-            let led_start_intensity = {
-                let saturated = if 100 < led_start_intensity {
-                    100
-                } else {
-                    led_start_intensity
-                } as f32;
-                ((saturated / 6.25) as u8).into()
-            };
-
             LightEmittingPattern {
                 phases_len: None,
-                phases: Vec::with_capacity(15),
-                global_mini_cycle_duration,
-                led_start_intensity,
+                phases: Vec::with_capacity(MAX_PHASES),
+                global_mini_cycle_duration: encode_global_mini_cycle_duration(
+                    global_mini_cycle_duration,
+                ),
+                led_start_intensity: encode_intensity(led_start_intensity),
                 repeat_count,
             }
         }
 
+        /// Constructor of `LightEmittingPattern`, validating arguments.
+        /// For the constraints, see [`new`](#method.new).
+        pub fn try_new(
+            global_mini_cycle_duration: u8,
+            led_start_intensity: u8,
+            repeat_count: u8,
+        ) -> Result<Self, InvalidHomeLightPattern> {
+            if global_mini_cycle_duration != 0 {
+                check_range(
+                    "global_mini_cycle_duration",
+                    global_mini_cycle_duration as u32,
+                    MIN_GLOBAL_MINI_CYCLE_DURATION as u32..=MAX_GLOBAL_MINI_CYCLE_DURATION as u32,
+                )?;
+            }
+            check_range("led_start_intensity", led_start_intensity as u32, 0..=100)?;
+            check_range("repeat_count", repeat_count as u32, 0..=15)?;
+
+            Ok(Self::new(
+                global_mini_cycle_duration,
+                led_start_intensity,
+                repeat_count.into(),
+            ))
+        }
+
+        /// Add a phase as it is.
+        /// Phases beyond [`MAX_PHASES`] are dropped when the pattern is encoded;
+        /// [`validate`](#method.validate) reports them.
+        ///
+        /// [`MAX_PHASES`]: constant.MAX_PHASES.html
         pub fn push_phase(&mut self, phase: LightEmittingPhase) {
             self.phases.push(phase);
         }
@@ -198,7 +305,28 @@ pub mod home_button {
             &self.phases
         }
 
+        /// Global mini cycle duration. Zero is off.
+        pub fn global_mini_cycle_duration(&self) -> Duration {
+            Duration::from_millis(
+                decode_global_mini_cycle_duration(self.global_mini_cycle_duration) as u64,
+            )
+        }
+
+        pub fn led_start_intensity(&self) -> u4 {
+            self.led_start_intensity
+        }
+
+        /// Value `0` is repeat forever.
+        pub fn repeat_count(&self) -> u4 {
+            self.repeat_count
+        }
+
         /// Add emitting phase to pattern.
+        /// If arguments not in line with constraints, args will be saturated,
+        /// and phases beyond [`MAX_PHASES`] are dropped when the pattern is encoded.
+        /// See [`try_add_phase`](#method.try_add_phase) to validate them.
+        ///
+        /// [`MAX_PHASES`]: constant.MAX_PHASES.html
         ///
         /// `fading_transition_duration` and `led_duration` is represented by 4-bit unsigned int
         /// in field and is treated as a multiplier of the `LightEmittingPattern.global_mini_cycle_duration`
@@ -207,39 +335,30 @@ pub mod home_button {
         /// Therefore, depending on the combination of the `LightEmittingPattern.global_mini_cycle_duration`
         /// and the specified value, different values may be regarded as the same value
         /// when converted to 4-bit unsigned int, and no difference may appear in the luminous pattern.
+        /// If `global_mini_cycle_duration` is 0, the durations are 0.
         ///
         /// * led_intensity (*%*) - 0 <= led_intensity <= 100
-        /// * fading_transition_duration (*ms*) - 0 < fading_transition_duration < self.global_mini_cycle_duration (ms) * 15
-        /// * led_duration (*ms*) - 0 < fading_transition_duration < self.global_mini_cycle_duration (ms) * 15
+        /// * fading_transition_duration (*ms*) - 0 <= fading_transition_duration <= self.global_mini_cycle_duration (ms) * 15
+        /// * led_duration (*ms*) - 0 <= led_duration <= self.global_mini_cycle_duration (ms) * 15
         pub fn add_phase(
             mut self,
             led_intensity: u8,
             fading_transition_duration: u16,
             led_duration: u16,
         ) -> Self {
-            let led_intensity = {
-                let saturated = if 100 < led_intensity {
-                    100
-                } else {
-                    led_intensity
-                } as f32;
-                ((saturated / 6.25) as u8).into()
+            let gmcd = decode_global_mini_cycle_duration(self.global_mini_cycle_duration);
+            let multiplier = |duration: u16| -> u4 {
+                match gmcd {
+                    0 => 0,
+                    gmcd => (duration / gmcd).min(15) as u8,
+                }
+                .into()
             };
-            let fading_transition_duration: u4 = {
-                let gmcd: u8 = self.global_mini_cycle_duration.into();
-                (fading_transition_duration / gmcd as u16) as u8
-            }
-            .into();
-            let led_duration = {
-                let gmcd: u8 = self.global_mini_cycle_duration.into();
-                (led_duration / gmcd as u16) as u8
-            }
-            .into();
 
             let phase = LightEmittingPhase {
-                led_intensity,
-                fading_transition_duration,
-                led_duration,
+                led_intensity: encode_intensity(led_intensity),
+                fading_transition_duration: multiplier(fading_transition_duration),
+                led_duration: multiplier(led_duration),
             };
 
             self.push_phase(phase);
@@ -247,6 +366,46 @@ pub mod home_button {
             self
         }
 
+        /// Add emitting phase to pattern, validating arguments.
+        /// For the constraints, see [`add_phase`](#method.add_phase).
+        pub fn try_add_phase(
+            self,
+            led_intensity: u8,
+            fading_transition_duration: u16,
+            led_duration: u16,
+        ) -> Result<Self, InvalidHomeLightPattern> {
+            if self.phases.len() >= MAX_PHASES {
+                return Err(InvalidHomeLightPattern::TooManyPhases(
+                    self.phases.len() + 1,
+                ));
+            }
+
+            let gmcd = decode_global_mini_cycle_duration(self.global_mini_cycle_duration);
+            if gmcd == 0 && (fading_transition_duration != 0 || led_duration != 0) {
+                return Err(InvalidHomeLightPattern::ZeroGlobalMiniCycleDuration);
+            }
+
+            check_range("led_intensity", led_intensity as u32, 0..=100)?;
+            let max_duration = gmcd as u32 * 15;
+            check_range(
+                "fading_transition_duration",
+                fading_transition_duration as u32,
+                0..=max_duration,
+            )?;
+            check_range("led_duration", led_duration as u32, 0..=max_duration)?;
+
+            Ok(self.add_phase(led_intensity, fading_transition_duration, led_duration))
+        }
+
+        /// Check that the pattern can be sent without truncation.
+        pub fn validate(&self) -> Result<(), InvalidHomeLightPattern> {
+            if self.phases.len() > MAX_PHASES {
+                return Err(InvalidHomeLightPattern::TooManyPhases(self.phases.len()));
+            }
+
+            Ok(())
+        }
+
         /// Does the 1st phase and then the LED stays on with LED Start Intensity.
         ///
         /// For more information about the arguments,
@@ -267,8 +426,63 @@ pub mod home_button {
 
             pattern.add_phase(led_intensity, fading_transition_duration, led_duration)
         }
+
+        /// Shortest global mini cycle duration (ms) whose multipliers cover `longest` ms.
+        fn global_mini_cycle_duration_for(longest: u16) -> u8 {
+            (1..=15u8)
+                .map(|code| decode_global_mini_cycle_duration(code.into()))
+                .find(|&gmcd| gmcd * 15 >= longest)
+                .map(|gmcd| gmcd.min(MAX_GLOBAL_MINI_CYCLE_DURATION as u16) as u8)
+                .unwrap_or(MAX_GLOBAL_MINI_CYCLE_DURATION)
+        }
+
+        fn millis(duration: Duration) -> u16 {
+            duration.as_millis().min(u16::MAX as u128) as u16
+        }
+
+        /// Fade in and out repeatedly. `period` is up to 5 seconds.
+        pub fn breathing(period: Duration) -> Self {
+            let half = Self::millis(period) / 2;
+            let gmcd = Self::global_mini_cycle_duration_for(half);
+
+            LightEmittingPattern::new(gmcd, 0, 0u8.into())
+                .add_phase(100, half, 0)
+                .add_phase(0, half, 0)
+        }
+
+        /// Two beats and a pause, repeatedly.
+        pub fn heartbeat() -> Self {
+            LightEmittingPattern::new(50, 0, 0u8.into())
+                .add_phase(100, 50, 100)
+                .add_phase(0, 50, 100)
+                .add_phase(100, 50, 100)
+                .add_phase(0, 200, 750)
+        }
+
+        /// Blink `times` (1 - 15) times. `on` and `off` are up to 2.6 seconds.
+        pub fn blink(times: u8, on: Duration, off: Duration) -> Self {
+            let (on, off) = (Self::millis(on), Self::millis(off));
+            let gmcd = Self::global_mini_cycle_duration_for(on.max(off));
+
+            LightEmittingPattern::new(gmcd, 0, times.clamp(1, 15).into())
+                .add_phase(100, 0, on)
+                .add_phase(0, 0, off)
+        }
+
+        /// Fade out from 100% over `duration`, which is up to 2.6 seconds.
+        pub fn fade_out(duration: Duration) -> Self {
+            let duration = Self::millis(duration);
+            let gmcd = Self::global_mini_cycle_duration_for(duration);
+
+            LightEmittingPattern::new(gmcd, 100, 1u8.into()).add_phase(0, duration, 0)
+        }
     }
 
+    /// Phases beyond [`MAX_PHASES`] are dropped.
+    /// [`LightEmittingPattern::validate`] tells whether the pattern is encoded as it is.
+    ///
+    /// [`MAX_PHASES`]: constant.MAX_PHASES.html
+    /// [`LightEmittingPattern::validate`]: struct.LightEmittingPattern.html#method.validate
     impl From<LightEmittingPattern> for [u8; 25] {
         fn from(s: LightEmittingPattern) -> [u8; 25] {
             fn nibbles_to_u8(high: u4, low: u4) -> u8 {
@@ -298,14 +512,14 @@ pub mod home_button {
             let mut even_phases = s
                 .phases
                 .iter()
-                .take(15)
+                .take(MAX_PHASES)
                 .enumerate()
                 .filter(|(idx, _)| idx % 2 == 0)
                 .map(|e| e.1);
             let mut odd_phases = s
                 .phases
                 .iter()
-                .take(15)
+                .take(MAX_PHASES)
                 .enumerate()
                 .filter(|(idx, _)| idx % 2 == 1)
                 .map(|e| e.1);
@@ -343,9 +557,301 @@ pub mod home_button {
             buf
         }
     }
+
+    impl From<[u8; 25]> for LightEmittingPattern {
+        fn from(buf: [u8; 25]) -> Self {
+            let high = |byte: u8| u4(byte >> 4);
+            let low = |byte: u8| u4(byte & 0x0F);
+
+            let number_of_phases = high(buf[0]);
+            // Zero phases with data of the 1st phase is a pattern made by `once`
+            let (phases_len, count) = match u8::from(number_of_phases) {
+                0 if buf[2..4] != [0, 0] => (Some(number_of_phases), 1),
+                n => (None, n as usize),
+            };
+
+            let mut phases = Vec::with_capacity(MAX_PHASES);
+            let mut buf_index = 2;
+            while phases.len() < count {
+                let intensities = buf[buf_index];
+                let even = buf[buf_index + 1];
+                phases.push(LightEmittingPhase {
+                    led_intensity: high(intensities),
+                    fading_transition_duration: high(even),
+                    led_duration: low(even),
+                });
+                buf_index += 2;
+
+                if phases.len() < count {
+                    let odd = buf[buf_index];
+                    phases.push(LightEmittingPhase {
+                        led_intensity: low(intensities),
+                        fading_transition_duration: high(odd),
+                        led_duration: low(odd),
+                    });
+                    buf_index += 1;
+                }
+            }
+
+            LightEmittingPattern {
+                phases_len,
+                phases,
+                global_mini_cycle_duration: low(buf[0]),
+                led_start_intensity: high(buf[1]),
+                repeat_count: low(buf[1]),
+            }
+        }
+    }
+}
+
+pub mod animation {
+    //! Animate player lights.
+    //!
+    //! Keyframes are sent in the background without waiting for replies,
+    //! so animations can run alongside input report modes such as `StandardFullMode`.
+    //!
+    //! # Usage
+    //! Blink an error code while reading input reports of the same device.
+    //! ```no_run
+    //! use joycon_rs::prelude::{*, lights::animation::*};
+    //! use std::sync::{Arc, Mutex};
+    //! use std::time::Duration;
+    //!
+    //! fn blink_error(
+    //!     device: &Arc<Mutex<JoyConDevice>>,
+    //!     standard_full_mode: &StandardFullMode<SimpleJoyConDriver>,
+    //! ) -> JoyConResult<()> {
+    //!     let _playback = PlayerLightsAnimator::new(device).play(LightsAnimation::blink_code(
+    //!         3,
+    //!         Duration::from_millis(300),
+    //!         Duration::from_millis(300),
+    //!         Duration::from_secs(1),
+    //!     ));
+    //!
+    //!     loop {
+    //!         dbg!(standard_full_mode.read_input_report()?);
+    //!     }
+    //! }
+    //! ```
+    //!
+    //! [`PlayerLightsAnimator`]: struct.PlayerLightsAnimator.html
+
+    use super::*;
+    use crate::joycon::driver::playback::Playback;
+    use crate::joycon::driver::rumble_sequencer::Repeat;
+    use std::time::{Duration, Instant};
+
+    /// State of player lights lasting for a duration.
+    #[derive(Debug, Clone, Hash, Eq, PartialEq)]
+    pub struct Keyframe {
+        pub lights: LightsStatus,
+        pub duration: Duration,
+    }
+
+    impl Keyframe {
+        pub fn new(light_up: &[LightUp], flash: &[Flash], duration: Duration) -> Self {
+            Keyframe {
+                lights: LightsStatus {
+                    light_up: light_up.to_vec(),
+                    flash: flash.to_vec(),
+                },
+                duration,
+            }
+        }
+
+        /// Argument of `SubCommand::SetPlayerLights`.
+        fn arg(&self) -> u8 {
+            self.lights.light_up.iter().fold(0, |arg, &l| arg | l as u8)
+                | self.lights.flash.iter().fold(0, |arg, &f| arg | f as u8)
+        }
+    }
+
+    /// Sequence of keyframes.
+    ///
+    /// # Example
+    /// ```
+    /// use joycon_rs::prelude::lights::{*, animation::*};
+    ///
+    /// // [SL Button] 💡💡📸🤔 [SR Button]
+    /// let progress = LightsAnimation::progress(0.6);
+    /// assert_eq!(progress.keyframes()[0].lights, LightsStatus {
+    ///     light_up: vec![LightUp::LED0, LightUp::LED1],
+    ///     flash: vec![Flash::LED2],
+    /// });
+    ///
+    /// // [SL Button] 💡🤔💡🤔 [SR Button]
+    /// let counter = LightsAnimation::counter(5);
+    /// assert_eq!(counter.keyframes()[0].lights.light_up, vec![LightUp::LED0, LightUp::LED2]);
+    /// ```
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct LightsAnimation {
+        keyframes: Vec<Keyframe>,
+        pub repeat: Repeat,
+    }
+
+    impl LightsAnimation {
+        /// Animation played once.
+        pub fn new() -> Self {
+            LightsAnimation {
+                keyframes: Vec::new(),
+                repeat: Repeat::Once,
+            }
+        }
+
+        pub fn push_keyframe(&mut self, keyframe: Keyframe) {
+            self.keyframes.push(keyframe);
+        }
+
+        pub fn keyframes(&self) -> &Vec<Keyframe> {
+            &self.keyframes
+        }
+
+        /// Add a keyframe.
+        pub fn keyframe(
+            mut self,
+            light_up: &[LightUp],
+            flash: &[Flash],
+            duration: Duration,
+        ) -> Self {
+            self.push_keyframe(Keyframe::new(light_up, flash, duration));
+            self
+        }
+
+        /// Set how many times the animation is played.
+        pub fn repeat(mut self, repeat: Repeat) -> Self {
+            self.repeat = repeat;
+            self
+        }
+
+        /// Duration of a loop.
+        pub fn loop_duration(&self) -> Duration {
+            self.keyframes.iter().map(|k| k.duration).sum()
+        }
+
+        /// A light running from SL to SR, forever.
+        pub fn chaser(step: Duration) -> Self {
+            LIGHT_UP
+                .iter()
+                .fold(LightsAnimation::new(), |animation, &l| {
+                    animation.keyframe(&[l], &[], step)
+                })
+                .repeat(Repeat::Forever)
+        }
+
+        /// `value` (0 - 15) in binary. LED0 is the lowest bit.
+        ///
+        /// Only the lowest 4 bits can be shown, so `value` over 15 is shown modulo 16.
+        pub fn counter(value: u8) -> Self {
+            let light_up = LIGHT_UP
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| value & (1 << i) != 0)
+                .map(|(_, &l)| l)
+                .collect::<Vec<_>>();
+
+            LightsAnimation::new().keyframe(&light_up, &[], Duration::from_millis(0))
+        }
+
+        /// Bar filled from SL by `ratio` (0.0 - 1.0). A partially filled LED flashes.
+        pub fn progress(ratio: f32) -> Self {
+            let filled = (ratio.clamp(0.0, 1.0) * 4.0).min(4.0);
+            let full = filled.floor() as usize;
+
+            let light_up = &LIGHT_UP[..full];
+            let flash = if full < 4 && filled > full as f32 {
+                &FLASH[full..=full]
+            } else {
+                &FLASH[..0]
+            };
+
+            LightsAnimation::new().keyframe(light_up, flash, Duration::from_millis(0))
+        }
+
+        /// All lights blink `code` times and pause, forever.
+        pub fn blink_code(code: u8, on: Duration, off: Duration, pause: Duration) -> Self {
+            let animation = (0..code).fold(LightsAnimation::new(), |animation, _| {
+                animation
+                    .keyframe(&LIGHT_UP, &[], on)
+                    .keyframe(&[], &[], off)
+            });
+
+            animation.keyframe(&[], &[], pause).repeat(Repeat::Forever)
+        }
+    }
+
+    impl Default for LightsAnimation {
+        fn default() -> Self {
+            LightsAnimation::new()
+        }
+    }
+
+    /// Plays animations of player lights on a device in the background.
+    ///
+    /// Keyframes are sent through the output scheduler of the device,
+    /// and the last keyframe stays lit after the animation ends.
+    #[derive(Debug, Clone)]
+    pub struct PlayerLightsAnimator {
+        device: Arc<Mutex<JoyConDevice>>,
+    }
+
+    impl PlayerLightsAnimator {
+        pub fn new(device: &Arc<Mutex<JoyConDevice>>) -> Self {
+            PlayerLightsAnimator {
+                device: Arc::clone(device),
+            }
+        }
+
+        /// Start playing the animation in the background.
+        pub fn play(&self, animation: LightsAnimation) -> LightsPlayback {
+            let device = Arc::clone(&self.device);
+
+            Playback::spawn(move |cancellation| {
+                let loops = match animation.repeat {
+                    Repeat::Once => Some(1),
+                    Repeat::Times(times) => Some(times),
+                    Repeat::Forever => None,
+                };
+                // Without duration, an animation forever would be a busy loop
+                let loops = if animation.loop_duration() == Duration::from_millis(0) {
+                    Some(loops.unwrap_or(1).min(1))
+                } else {
+                    loops
+                };
+
+                let mut next_keyframe = Instant::now();
+                let mut played = 0;
+                while loops.is_none_or(|loops| played < loops) {
+                    for keyframe in &animation.keyframes {
+                        JoyConDevice::send_paced(
+                            &device,
+                            Command::RumbleAndSubCommand as u8,
+                            SubCommand::SetPlayerLights as u8,
+                            &[keyframe.arg()],
+                        )?;
+
+                        next_keyframe += keyframe.duration;
+                        if cancellation.sleep_until(next_keyframe) {
+                            return Ok(());
+                        }
+                    }
+                    played += 1;
+                }
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Handle of an animation being played.
+    /// Dropping the handle, or cancelling it, stops playing. The lights stay as they are.
+    pub type LightsPlayback = Playback;
 }
 
 /// Operations of player lights.
+///
+/// Sub-commands are sent again according to the driver's [`RetryPolicy`] while no reply arrives.
+///
+/// [`RetryPolicy`]: ../struct.RetryPolicy.html
 pub trait Lights: JoyConDriver {
     const LIGHT_UP: [LightUp; 4] = LIGHT_UP;
     const FLASH: [Flash; 4] = FLASH;
@@ -425,6 +931,15 @@ pub trait Lights: JoyConDriver {
         Ok(reply)
     }
 
+    /// Show the player slot (0 - 7) on player lights as Nintendo Switch does.
+    /// Lights of other slots are turned off.
+    ///
+    /// See [`PLAYER_LIGHTS`](constant.PLAYER_LIGHTS.html).
+    fn set_player_slot(&mut self, slot: usize) -> JoyConResult<SubCommandReply<[u8; 362]>> {
+        let light_up = PLAYER_LIGHTS.get(slot).copied().unwrap_or(&[]);
+        self.set_player_lights(light_up, &[])
+    }
+
     /// Get status of player lights on controller.
     ///
     /// # Example
@@ -457,6 +972,9 @@ pub trait Lights: JoyConDriver {
 
     /// Set HOME light.
     ///
+    /// Left Joy-Con has no HOME light, and this returns `JoyConDeviceError::NoHomeLight` for it.
+    /// Patterns of more than 15 phases are rejected instead of being truncated.
+    ///
     /// # Example
     /// ```no_run
     /// use joycon_rs::prelude::{*, lights::{*, home_button::*}};
@@ -482,6 +1000,16 @@ pub trait Lights: JoyConDriver {
         &mut self,
         pattern: &home_button::LightEmittingPattern,
     ) -> JoyConResult<SubCommandReply<[u8; 362]>> {
+        pattern.validate()?;
+
+        let device_type = self.devices().first().map(|device| match device.lock() {
+            Ok(d) => d.device_type(),
+            Err(e) => e.into_inner().device_type(),
+        });
+        if let Some(device_type) = device_type.filter(|d| !d.has_home_light()) {
+            return Err(JoyConDeviceError::NoHomeLight(device_type).into());
+        }
+
         let arg: [u8; 25] = pattern.clone().into();
         self.send_sub_command(SubCommand::SetHOMELight, &arg)
     }
@@ -1,7 +1,9 @@
 use super::*;
 pub use global_packet_number::GlobalPacketNumber;
 pub use joycon_features::{IMUConfig, JoyConFeature};
-pub use rumble::Rumble;
+pub use retry_policy::RetryPolicy;
+pub use rumble::{Rumble, RumbleBand};
+pub use rumble_limiter::RumbleLimiter;
 pub use simple_joycon_driver::SimpleJoyConDriver;
 use std::collections::HashSet;
 use std::convert::TryFrom;
@@ -67,6 +69,16 @@ mod rumble;
 
 pub mod joycon_features;
 
+pub mod retry_policy;
+
+pub mod rumble_limiter;
+
+pub mod playback;
+
+pub mod rumble_sequencer;
+
+pub mod audio_rumble;
+
 mod global_packet_number {
     use std::ops::Add;
 
@@ -139,15 +151,23 @@ where
 }
 
 pub trait JoyConDriver {
-    /// If a sub-command is sent and no ACK packet is returned, tread again for the number of times of this value.
-    const ACK_TRY: usize = 5;
-
     /// If `true`, driver does not read and check reply of sub-command.
     fn valid_reply(&self) -> bool;
 
     fn set_valid_reply(&mut self, valid: bool);
 
+    /// Timeout and retry policy for sub-commands. `RetryPolicy::default()` by default.
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::default()
+    }
+
+    /// Set the timeout and retry policy for sub-commands.
+    /// Drivers keeping the default [`retry_policy`](#method.retry_policy) ignore this.
+    fn set_retry_policy(&mut self, _policy: RetryPolicy) {}
+
     /// Send command to Joy-Con
+    ///
+    /// Returns `Ok(0)` if a rumble-only report was superseded by a newer one before being sent.
     fn write(&self, data: &[u8]) -> JoyConResult<usize>;
 
     /// Read reply from Joy-Con
@@ -165,9 +185,21 @@ pub trait JoyConDriver {
     /// Set rumble status.
     fn set_rumble_status(&mut self, rumble_l_r: (Option<Rumble>, Option<Rumble>));
 
+    /// Safety layer applied by [`rumble`](#method.rumble). `None` sends rumble as it is.
+    fn rumble_limiter_mut(&mut self) -> Option<&mut RumbleLimiter> {
+        None
+    }
+
     /// Set rumble status and send rumble command to JoyCon.
     /// If Joy-Con's rumble feature isn't activated, activate it.
+    ///
+    /// Rumble is limited by [`rumble_limiter_mut`](#method.rumble_limiter_mut) beforehand.
     fn rumble(&mut self, rumble_l_r: (Option<Rumble>, Option<Rumble>)) -> JoyConResult<usize> {
+        let rumble_l_r = match self.rumble_limiter_mut() {
+            Some(limiter) => limiter.limit(rumble_l_r)?,
+            None => rumble_l_r,
+        };
+
         if !self.enabled_features().contains(&JoyConFeature::Vibration) {
             self.enable_feature(JoyConFeature::Vibration)?;
         }
@@ -186,37 +218,54 @@ pub trait JoyConDriver {
         sub_command: u8,
         data: &[u8],
     ) -> JoyConResult<usize> {
-        let mut buf = [0x0; 0x40];
-        // set command
-        buf[0] = command;
-        // set packet number
-        buf[1] = self.global_packet_number();
+        let buf = output_report(
+            command,
+            self.global_packet_number(),
+            self.get_rumble_status(),
+            sub_command,
+            data,
+        );
         // increase packet number
         self.increase_global_packet_number();
 
-        // rumble
-        let (rumble_l, rumble_r) = self.get_rumble_status();
-        if let Some(rumble_l) = rumble_l {
-            let rumble_left: [u8; 4] = rumble_l.into();
-            buf[2..6].copy_from_slice(&rumble_left);
-        }
-        if let Some(rumble_r) = rumble_r {
-            let rumble_right: [u8; 4] = rumble_r.into();
-            buf[6..10].copy_from_slice(&rumble_right);
-        }
+        // send command
+        self.write(&buf)
+    }
 
-        // set sub command
-        buf[10] = sub_command;
-        // set data
-        buf[11..11 + data.len()].copy_from_slice(data);
+    /// Send command, sub-command, and data through a shared reference.
+    ///
+    /// The packet number is taken from the first of [`devices`](#tymethod.devices),
+    /// advancing the counter shared by everything sending output reports to the device.
+    fn send_output_report(&self, command: u8, sub_command: u8, data: &[u8]) -> JoyConResult<usize> {
+        let packet_number = match self.devices().first() {
+            Some(device) => match device.lock() {
+                Ok(d) => d,
+                Err(e) => e.into_inner(),
+            }
+            .take_global_packet_number(),
+            None => return Err(JoyConError::Disconnected),
+        };
+
+        let buf = output_report(
+            command,
+            packet_number.into(),
+            self.get_rumble_status(),
+            sub_command,
+            data,
+        );
 
-        // send command
         self.write(&buf)
     }
 
     /// Send sub-command, and data (sub-command's arguments) with u8 integers
     /// This returns ACK packet for the command or Error.
     ///
+    /// The reply is matched by sub-command id, and the sub-command is sent again
+    /// according to [`retry_policy`](#tymethod.retry_policy) while no ACK arrives.
+    /// Other input reports received meanwhile are handed to [`forward_input_reports`](#method.forward_input_reports).
+    ///
+    /// If no reply arrives at all, this returns `JoyConError::SubCommandTimeout`.
+    ///
     /// # Notice
     /// If you are using non-blocking mode,
     /// it is more likely to fail to validate the sub command reply.
@@ -225,29 +274,76 @@ pub trait JoyConDriver {
         sub_command: u8,
         data: &[u8],
     ) -> JoyConResult<SubCommandReply<[u8; 362]>> {
-        use input_report_mode::sub_command_mode::AckByte;
-
-        self.send_command_raw(1, sub_command, data)?;
-
-        // check reply
-        if self.valid_reply() {
-            std::iter::repeat(())
-                .take(Self::ACK_TRY)
-                .flat_map(|()| {
-                    let mut buf = [0u8; 362];
-                    self.read(&mut buf).ok()?;
-                    let ack_byte = AckByte::from(buf[13]);
-
-                    match ack_byte {
-                        AckByte::Ack { .. } => Some(buf),
-                        AckByte::Nack => None,
-                    }
-                })
-                .next()
-                .map(SubCommandReply::Checked)
-                .ok_or_else(|| JoyConError::SubCommandError(sub_command, Vec::new()))
-        } else {
-            Ok(SubCommandReply::Unchecked)
+        use input_report_mode::sub_command_mode::{Correlation, SubCommandCorrelator};
+
+        if !self.valid_reply() {
+            self.send_command_raw(1, sub_command, data)?;
+            return Ok(SubCommandReply::Unchecked);
+        }
+
+        let mut nacked = false;
+        let reply = self.retry_policy().run(|timeout| {
+            self.send_command_raw(1, sub_command, data)?;
+
+            // check reply
+            let mut correlator = SubCommandCorrelator::new(sub_command, timeout);
+            let reply = correlator.wait(self);
+            self.forward_input_reports(correlator.into_forwarded());
+
+            match reply? {
+                Some(Correlation::Ack(reply)) => Ok(Some(reply)),
+                Some(_) => {
+                    nacked = true;
+                    Ok(None)
+                }
+                None => {
+                    nacked = false;
+                    Ok(None)
+                }
+            }
+        })?;
+
+        match reply {
+            Some(reply) => Ok(SubCommandReply::Checked(reply)),
+            None if nacked => Err(JoyConError::SubCommandError(sub_command, Vec::new())),
+            None => Err(JoyConError::SubCommandTimeout(sub_command)),
+        }
+    }
+
+    /// Hand input reports received while waiting for a sub-command reply to the report consumer.
+    /// By default, they are returned by the next reads from the device.
+    ///
+    /// The default only knows where the reports came from if the driver deals with one device,
+    /// and discards them otherwise rather than handing them to the wrong device.
+    /// Drivers dealing with several devices should override this.
+    fn forward_input_reports(&self, reports: Vec<[u8; 362]>) {
+        if reports.is_empty() {
+            return;
+        }
+
+        if let [device] = self.devices().as_slice() {
+            let mut device = match device.lock() {
+                Ok(d) => d,
+                Err(e) => e.into_inner(),
+            };
+            reports
+                .iter()
+                .for_each(|report| device.forward_report(report));
+        }
+    }
+
+    /// Hand a sub-command reply read by a report consumer back to the sender waiting for it.
+    /// By default, it is kept by the device, and taken by [`send_sub_command_raw`](#method.send_sub_command_raw).
+    ///
+    /// Like [`forward_input_reports`](#method.forward_input_reports), drivers dealing with
+    /// several devices should override this.
+    fn keep_reply(&self, report: &[u8]) {
+        if let [device] = self.devices().as_slice() {
+            match device.lock() {
+                Ok(d) => d,
+                Err(e) => e.into_inner(),
+            }
+            .keep_reply(report);
         }
     }
 
@@ -295,10 +391,53 @@ pub trait JoyConDriver {
     /// Get Enabled features.
     fn enabled_features(&self) -> &HashSet<JoyConFeature>;
 
+    /// Get the config of IMU(6-Axis sensor) if enabled.
+    fn imu_config(&self) -> Option<IMUConfig> {
+        self.enabled_features()
+            .iter()
+            .find_map(|feature| match feature {
+                JoyConFeature::IMUFeature(config) => Some(*config),
+                _ => None,
+            })
+    }
+
     /// Get Joy-Con devices deal with.
     fn devices(&self) -> Vec<Arc<Mutex<JoyConDevice>>>;
 }
 
+/// Build an output report: command, packet number, rumble data, sub-command and its arguments.
+pub(crate) fn output_report(
+    command: u8,
+    global_packet_number: u8,
+    rumble_l_r: (Option<Rumble>, Option<Rumble>),
+    sub_command: u8,
+    data: &[u8],
+) -> [u8; 0x40] {
+    let mut buf = [0x0; 0x40];
+    // set command
+    buf[0] = command;
+    // set packet number
+    buf[1] = global_packet_number;
+
+    // rumble
+    let (rumble_l, rumble_r) = rumble_l_r;
+    if let Some(rumble_l) = rumble_l {
+        let rumble_left: [u8; 4] = rumble_l.into();
+        buf[2..6].copy_from_slice(&rumble_left);
+    }
+    if let Some(rumble_r) = rumble_r {
+        let rumble_right: [u8; 4] = rumble_r.into();
+        buf[6..10].copy_from_slice(&rumble_right);
+    }
+
+    // set sub command
+    buf[10] = sub_command;
+    // set data
+    buf[11..11 + data.len()].copy_from_slice(data);
+
+    buf
+}
+
 pub mod input_report_mode;
 
 pub mod lights;
@@ -0,0 +1,98 @@
+//! Handles of playbacks running in the background,
+//! such as rumble sequences and animations of player lights.
+
+use super::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::Instant;
+
+/// Handle of a playback running in the background.
+/// Dropping the handle cancels the playback.
+#[derive(Debug)]
+pub struct Playback {
+    cancelled: Arc<AtomicBool>,
+    handle: Option<JoinHandle<JoyConResult<()>>>,
+}
+
+impl Playback {
+    /// Run `play` in the background. It should return soon after the playback is cancelled.
+    pub(crate) fn spawn<F>(play: F) -> Self
+    where
+        F: FnOnce(&Cancellation) -> JoyConResult<()> + Send + 'static,
+    {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let cancellation = Cancellation(Arc::clone(&cancelled));
+        let handle = std::thread::spawn(move || play(&cancellation));
+
+        Playback {
+            cancelled,
+            handle: Some(handle),
+        }
+    }
+
+    /// Whether the playback has ended or stopped by error.
+    pub fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    /// Stop playing, and wait for the playback to clean up.
+    pub fn cancel(self) -> JoyConResult<()> {
+        self.notify_cancel();
+        self.join()
+    }
+
+    /// Wait for the playback to end.
+    pub fn join(mut self) -> JoyConResult<()> {
+        match self.handle.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(e)) => std::panic::resume_unwind(e),
+            None => Ok(()),
+        }
+    }
+
+    fn notify_cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        // Wake the playback sleeping until the next frame
+        if let Some(handle) = &self.handle {
+            handle.thread().unpark();
+        }
+    }
+}
+
+impl Drop for Playback {
+    fn drop(&mut self) {
+        if self.handle.is_some() {
+            self.notify_cancel();
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Cancellation of a [`Playback`], seen from the playback.
+///
+/// [`Playback`]: struct.Playback.html
+#[derive(Debug)]
+pub(crate) struct Cancellation(Arc<AtomicBool>);
+
+impl Cancellation {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Sleep until `deadline` unless cancelled meanwhile. Returns whether cancelled.
+    pub fn sleep_until(&self, deadline: Instant) -> bool {
+        loop {
+            if self.is_cancelled() {
+                return true;
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            std::thread::park_timeout(deadline - now);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::prelude::*;
+use std::time::{Duration, Instant};
+
+/// Wait between attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backoff {
+    /// Retry immediately.
+    None,
+    /// Wait for the same duration every time.
+    Constant(Duration),
+    /// Wait for `initial`, then double the wait on every retry up to `max`.
+    Exponential { initial: Duration, max: Duration },
+}
+
+impl Backoff {
+    /// Wait before the `retry`-th retry (0 origin).
+    pub fn delay(&self, retry: usize) -> Duration {
+        match *self {
+            Backoff::None => Duration::from_millis(0),
+            Backoff::Constant(delay) => delay,
+            Backoff::Exponential { initial, max } => {
+                let factor = 1u32.checked_shl(retry as u32).unwrap_or(u32::MAX);
+                initial
+                    .checked_mul(factor)
+                    .map(|delay| delay.min(max))
+                    .unwrap_or(max)
+            }
+        }
+    }
+}
+
+/// Timeout and retry policy for sub-commands.
+///
+/// Each attempt sends the request and waits for the reply for `attempt_timeout`.
+/// If no valid reply arrives, the request is sent again after `backoff`,
+/// up to `attempts` times or until `deadline` passes since the first attempt.
+///
+/// # Example
+/// ```no_run
+/// use joycon_rs::prelude::*;
+/// use joycon_rs::joycon::retry_policy::Backoff;
+/// use std::sync::{Arc, Mutex};
+/// use std::time::Duration;
+///
+/// // Be patient with congested Bluetooth
+/// fn patient_driver(device: &Arc<Mutex<JoyConDevice>>) -> JoyConResult<SimpleJoyConDriver> {
+///     let policy = RetryPolicy {
+///         attempts: 10,
+///         attempt_timeout: Duration::from_millis(300),
+///         deadline: Some(Duration::from_secs(5)),
+///         backoff: Backoff::Constant(Duration::from_millis(50)),
+///     };
+///
+///     SimpleJoyConDriver::with_retry_policy(device, policy)
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RetryPolicy {
+    /// Max number of attempts. At least one attempt is made.
+    pub attempts: usize,
+    /// Time to wait for the reply in each attempt.
+    pub attempt_timeout: Duration,
+    /// Time limit of all attempts. `None` is unlimited.
+    pub deadline: Option<Duration>,
+    /// Wait between attempts.
+    pub backoff: Backoff,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            attempts: 5,
+            attempt_timeout: Duration::from_millis(200),
+            deadline: Some(Duration::from_secs(2)),
+            backoff: Backoff::Exponential {
+                initial: Duration::from_millis(10),
+                max: Duration::from_millis(100),
+            },
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Run `attempt` according to the policy.
+    ///
+    /// `attempt` receives the time it may wait for the reply,
+    /// and returns `Ok(None)` if no valid reply has arrived.
+    /// Errors are returned immediately without retry.
+    ///
+    /// This returns `Ok(None)` if no attempt succeeded.
+    pub fn run<T, F>(&self, mut attempt: F) -> JoyConResult<Option<T>>
+    where
+        F: FnMut(Duration) -> JoyConResult<Option<T>>,
+    {
+        let deadline = self.deadline.map(|deadline| Instant::now() + deadline);
+
+        for retry in 0..self.attempts.max(1) {
+            let timeout = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if deadline <= now {
+                        break;
+                    }
+                    self.attempt_timeout.min(deadline - now)
+                }
+                None => self.attempt_timeout,
+            };
+
+            if let Some(reply) = attempt(timeout)? {
+                return Ok(Some(reply));
+            }
+
+            if retry + 1 < self.attempts {
+                let mut delay = self.backoff.delay(retry);
+                if let Some(deadline) = deadline {
+                    delay = delay.min(deadline.saturating_duration_since(Instant::now()));
+                }
+                std::thread::sleep(delay);
+            }
+        }
+
+        Ok(None)
+    }
+}
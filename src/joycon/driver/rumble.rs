@@ -1,9 +1,19 @@
+use crate::result::InvalidRumbleData;
+use std::convert::TryFrom;
+
 /// Rumble data for vibration.
 ///
+/// Rumble consists of a high band and a low band.
+/// [`Rumble::new`] vibrates both bands at the same frequency,
+/// and [`Rumble::dual_band`] vibrates them independently.
+///
 /// # Notice
 /// Constraints exist.
-/// * frequency - 0.0 < freq < 1252.0
-/// * amplitude - 0.0 < amp < 1.799.0
+/// * frequency - high band: 80.0 <= freq <= 1252.57, low band: 40.87 <= freq <= 626.28
+/// * amplitude - 0.0 <= amp <= 1.799
+///
+/// [`Rumble::new`]: #method.new
+/// [`Rumble::dual_band`]: #method.dual_band
 ///
 /// # Example
 /// ```no_run
@@ -34,47 +44,110 @@
 ///```
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rumble {
-    frequency: f32,
-    amplitude: f32,
+    high: RumbleBand,
+    low: RumbleBand,
 }
 
 impl Rumble {
+    /// Max amplitude.
+    pub const MAX_AMPLITUDE: f32 = 1.799;
+    /// Max amplitude safe for the integrity of the linear resonant actuators.
+    pub const SAFE_AMPLITUDE: f32 = 1.003;
+
+    /// Frequency of the high band.
     pub fn frequency(self) -> f32 {
-        self.frequency
+        self.high.frequency
     }
 
+    /// The larger amplitude of the two bands.
     pub fn amplitude(self) -> f32 {
-        self.amplitude
+        self.high.amplitude.max(self.low.amplitude)
+    }
+
+    /// High band (81 Hz - 1253 Hz).
+    pub fn high(self) -> RumbleBand {
+        self.high
+    }
+
+    /// Low band (41 Hz - 626 Hz).
+    pub fn low(self) -> RumbleBand {
+        self.low
     }
 
     /// Constructor of Rumble.
+    /// Both bands vibrate at the frequency and the amplitude.
     /// If arguments not in line with constraints, args will be saturated.
     pub fn new(freq: f32, amp: f32) -> Self {
-        let freq = if freq < 0.0 {
-            0.0
-        } else if freq > 1252.0 {
-            1252.0
-        } else {
-            freq
-        };
+        let band = RumbleBand::new(freq, amp);
 
-        let amp = if amp < 0.0 {
-            0.0
-        } else if amp > 1.799 {
-            1.799
-        } else {
-            amp
+        Rumble::dual_band(band, band)
+    }
+
+    /// Constructor of Rumble with independent high band and low band.
+    /// Frequencies out of the range of each band will be saturated.
+    ///
+    /// # Example
+    /// ```
+    /// use joycon_rs::prelude::*;
+    ///
+    /// let rumble = Rumble::dual_band(RumbleBand::new(320.0, 1.003), RumbleBand::new(160.0, 0.0));
+    /// let data: [u8; 4] = rumble.into();
+    /// assert_eq!(data, [0x00, 0xC9, 0x40, 0x40]);
+    /// ```
+    pub fn dual_band(high: RumbleBand, low: RumbleBand) -> Self {
+        let high = RumbleBand {
+            frequency: high.frequency.clamp(
+                RumbleBand::HIGH_BAND_MIN_FREQUENCY,
+                RumbleBand::HIGH_BAND_MAX_FREQUENCY,
+            ),
+            ..high
+        };
+        let low = RumbleBand {
+            frequency: low.frequency.clamp(
+                RumbleBand::LOW_BAND_MIN_FREQUENCY,
+                RumbleBand::LOW_BAND_MAX_FREQUENCY,
+            ),
+            ..low
         };
 
-        Self {
-            frequency: freq,
-            amplitude: amp,
-        }
+        Self { high, low }
     }
 
     /// The amplitudes over 1.003 are not safe for the integrity of the linear resonant actuators.
     pub fn is_safe(self) -> bool {
-        self.amplitude < 1.003
+        self.high.amplitude < Self::SAFE_AMPLITUDE && self.low.amplitude < Self::SAFE_AMPLITUDE
+    }
+
+    /// Decodes the rumble data of the left and right side in an output report.
+    /// A side filled with zeros has no rumble data.
+    ///
+    /// # Example
+    /// ```
+    /// use joycon_rs::prelude::*;
+    ///
+    /// let report = [0x10, 0x00, 0x00, 0x01, 0x40, 0x40, 0x00, 0x00, 0x00, 0x00];
+    /// let (left, right) = Rumble::decode_output_report(&report).unwrap();
+    /// assert_eq!(left, Some(Rumble::stop()));
+    /// assert_eq!(right, None);
+    /// ```
+    pub fn decode_output_report(
+        report: &[u8],
+    ) -> Result<(Option<Rumble>, Option<Rumble>), InvalidRumbleData> {
+        if report.len() < 10 {
+            return Err(InvalidRumbleData::InvalidOutputReport(report.to_vec()));
+        }
+
+        let decode = |data: &[u8]| {
+            let mut buf = [0u8; 4];
+            buf.copy_from_slice(data);
+            if buf == [0u8; 4] {
+                Ok(None)
+            } else {
+                Rumble::try_from(buf).map(Some)
+            }
+        };
+
+        Ok((decode(&report[2..6])?, decode(&report[6..10])?))
     }
 
     /// Generates stopper of rumbling.
@@ -86,57 +159,215 @@ impl Rumble {
     /// // Make JoyCon stop rambling.
     /// rumbling_controller_driver.rumble((Some(Rumble::stop()),Some(Rumble::stop()))).unwrap();
     /// ```
+    ///
+    /// ```
+    /// # use joycon_rs::prelude::*;
+    /// let data: [u8; 4] = Rumble::stop().into();
+    /// assert_eq!(data, [0x00, 0x01, 0x40, 0x40]);
+    /// ```
     pub fn stop() -> Self {
         Self {
-            frequency: 0.0,
-            amplitude: 0.0,
+            high: RumbleBand::new(320.0, 0.0),
+            low: RumbleBand::new(160.0, 0.0),
         }
     }
 }
 
-impl From<Rumble> for [u8; 4] {
-    fn from(s: Rumble) -> [u8; 4] {
-        let encoded_hex_freq = f32::round(f32::log2(s.frequency / 10.0) * 32.0) as u8;
+/// Frequency and amplitude of a band of HD rumble.
+///
+/// The actuator of Joy-Con vibrates at two bands, high band and low band, independently.
+/// See [`Rumble::dual_band`].
+///
+/// [`Rumble::dual_band`]: struct.Rumble.html#method.dual_band
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RumbleBand {
+    frequency: f32,
+    amplitude: f32,
+}
+
+impl RumbleBand {
+    pub const HIGH_BAND_MIN_FREQUENCY: f32 = 80.0;
+    pub const HIGH_BAND_MAX_FREQUENCY: f32 = 1252.57;
+    pub const LOW_BAND_MIN_FREQUENCY: f32 = 40.87;
+    pub const LOW_BAND_MAX_FREQUENCY: f32 = 626.28;
 
-        let hf_freq: u16 = (encoded_hex_freq as u16).saturating_sub(0x60) * 4;
-        let lf_freq: u8 = encoded_hex_freq.saturating_sub(0x41) + 1;
+    /// Amplitudes that can be encoded, indexed by the encoded value.
+    ///
+    /// ref. https://github.com/dekuNukem/Nintendo_Switch_Reverse_Engineering/blob/master/rumble_data_table.md
+    pub const AMPLITUDE_TABLE: [f32; 128] = [
+        0.000000, 0.007843, 0.011823, 0.014060, 0.016720, 0.019884, 0.023646, 0.028120, 0.033440,
+        0.039768, 0.047292, 0.056240, 0.066881, 0.079535, 0.094584, 0.112480, 0.117460, 0.122660,
+        0.128091, 0.133762, 0.139684, 0.145868, 0.152327, 0.159071, 0.166113, 0.173468, 0.181148,
+        0.189168, 0.197543, 0.206289, 0.215422, 0.224960, 0.229886, 0.234920, 0.240064, 0.245321,
+        0.250692, 0.256182, 0.261791, 0.267524, 0.273382, 0.279368, 0.285486, 0.291737, 0.298125,
+        0.304653, 0.311324, 0.318141, 0.325108, 0.332227, 0.339502, 0.346936, 0.354533, 0.362296,
+        0.370229, 0.378336, 0.386620, 0.395086, 0.403738, 0.412578, 0.421613, 0.430845, 0.440279,
+        0.449920, 0.459772, 0.469840, 0.480128, 0.490641, 0.501385, 0.512364, 0.523583, 0.535048,
+        0.546764, 0.558736, 0.570971, 0.583474, 0.596250, 0.609306, 0.622649, 0.636283, 0.650216,
+        0.664453, 0.679003, 0.693871, 0.709065, 0.724592, 0.740458, 0.756672, 0.773241, 0.790173,
+        0.807475, 0.825157, 0.843225, 0.861689, 0.880558, 0.899840, 0.919544, 0.939679, 0.960255,
+        0.981282, 1.002769, 1.024727, 1.047166, 1.070096, 1.093528, 1.117473, 1.141942, 1.166948,
+        1.192501, 1.218613, 1.245297, 1.272566, 1.300431, 1.328907, 1.358006, 1.387743, 1.41813,
+        1.449183, 1.480916, 1.513344, 1.546482, 1.580345, 1.614951, 1.650313, 1.68645, 1.723379,
+        1.761116, 1.799679,
+    ];
 
-        let encoded_hex_amp = if s.amplitude > 0.23 {
-            f32::round(f32::log2(s.amplitude * 8.7) * 32.0) as u8
-        } else if s.amplitude > 0.12 {
-            f32::round(f32::log2(s.amplitude * 17.0) * 16.0) as u8
+    /// Constructor of RumbleBand.
+    /// If arguments not in line with constraints, args will be saturated.
+    ///
+    /// * frequency - 0.0 <= freq. It is saturated again within the range of the band by [`Rumble::dual_band`].
+    /// * amplitude - 0.0 <= amp <= 1.799
+    ///
+    /// [`Rumble::dual_band`]: struct.Rumble.html#method.dual_band
+    pub fn new(frequency: f32, amplitude: f32) -> Self {
+        RumbleBand {
+            frequency: frequency.max(0.0),
+            amplitude: amplitude.clamp(0.0, Rumble::MAX_AMPLITUDE),
+        }
+    }
+
+    pub fn frequency(self) -> f32 {
+        self.frequency
+    }
+
+    pub fn amplitude(self) -> f32 {
+        self.amplitude
+    }
+
+    /// Band of the encoded frequency and the index of the amplitude table.
+    fn decode(encoded_frequency: f32, encoded_amplitude: u8) -> Self {
+        RumbleBand {
+            frequency: 10.0 * f32::exp2(encoded_frequency / 32.0),
+            amplitude: Self::AMPLITUDE_TABLE[encoded_amplitude as usize],
+        }
+    }
+
+    /// Encoded frequency: `round(log2(frequency / 10) * 32)`.
+    fn encoded_frequency(self) -> u8 {
+        f32::round(f32::log2(self.frequency / 10.0) * 32.0) as u8
+    }
+
+    /// Index of the nearest amplitude in the amplitude table.
+    fn encoded_amplitude(self) -> u8 {
+        let table = &Self::AMPLITUDE_TABLE;
+        let upper = table.partition_point(|&amp| amp < self.amplitude);
+
+        let index = if upper == 0 {
+            0
+        } else if upper == table.len() {
+            table.len() - 1
+        } else if table[upper] - self.amplitude < self.amplitude - table[upper - 1] {
+            upper
         } else {
-            f32::round(((f32::log2(s.amplitude) * 32.0) - 96.0) / (4.0 - 2.0 * s.amplitude)) as u8
+            upper - 1
         };
 
-        let hf_amp: u16 = {
-            let hf_amp: u16 = encoded_hex_amp as u16 * 2;
-            if hf_amp > 0x01FC {
-                0x01FC
-            } else {
-                hf_amp
-            }
-        }; // encoded_hex_amp<<1;
-        let lf_amp: u8 = {
-            let lf_amp = encoded_hex_amp / 2 + 64;
-            if lf_amp > 0x7F {
-                0x7F
-            } else {
-                lf_amp
-            }
-        }; // (encoded_hex_amp>>1)+0x40;
+        index as u8
+    }
+}
+
+impl From<Rumble> for [u8; 4] {
+    fn from(s: Rumble) -> [u8; 4] {
+        // frequencies are saturated in the range of each band
+        let hf_freq: u16 = (s.high.encoded_frequency().clamp(0x60, 0xDF) - 0x60) as u16 * 4;
+        let lf_freq: u8 = s.low.encoded_frequency().clamp(0x41, 0xBF) - 0x40;
+
+        let hf_amp: u16 = s.high.encoded_amplitude() as u16 * 2;
+        let lf_amp: u16 = {
+            let encoded = s.low.encoded_amplitude() as u16;
+            // the lowest bit is carried by the MSB of the frequency byte
+            0x40 + encoded / 2 + (encoded % 2) * 0x8000
+        };
 
         let mut buf = [0u8; 4];
 
         // HF: Byte swapping
         buf[0] = (hf_freq & 0xFF) as u8;
-        // buf[1] = (hf_amp + ((hf_freq >> 8) & 0xFF)) as u8; //Add amp + 1st byte of frequency to amplitude byte
-        buf[1] = (hf_amp + (hf_freq.wrapping_shr(8) & 0xFF)) as u8; //Add amp + 1st byte of frequency to amplitude byte
+        buf[1] = (hf_amp + ((hf_freq >> 8) & 0xFF)) as u8; //Add amp + 1st byte of frequency to amplitude byte
 
         // LF: Byte swapping
-        buf[2] = lf_freq.saturating_add(lf_amp.wrapping_shr(8));
-        buf[3] = lf_amp;
+        buf[2] = lf_freq + ((lf_amp >> 8) & 0xFF) as u8;
+        buf[3] = (lf_amp & 0xFF) as u8;
 
         buf
     }
 }
+
+/// Decodes rumble data into frequencies and amplitudes.
+///
+/// Read as a little-endian `u32`, the top 2 bits of rumble data tell the frame type,
+/// and the low 2 bits its sub-type.
+/// Only the standard encoding (type 1, sub-type 0), a pair of the high band and the low band,
+/// is decoded. Other frame types and sub-types carry multiple pulses or frequencies
+/// in layouts that aren't documented well enough to be decoded,
+/// and they are rejected as [`InvalidRumbleData::UnsupportedEncoding`].
+/// So is the low band frequency 0, which is below the range of the band.
+///
+/// Frequencies and amplitudes are quantized by the encoding,
+/// so decoding rumble data and encoding it again yields the same data.
+///
+/// [`InvalidRumbleData::UnsupportedEncoding`]: ../../result/enum.InvalidRumbleData.html#variant.UnsupportedEncoding
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::*;
+/// use std::convert::TryFrom;
+///
+/// let data = [0x00, 0xC9, 0x40, 0x40];
+/// let rumble = Rumble::try_from(data).unwrap();
+/// assert_eq!(rumble.high().frequency(), 320.0);
+/// assert_eq!(rumble.high().amplitude(), 1.002769);
+/// assert_eq!(rumble.low().amplitude(), 0.0);
+///
+/// let encoded: [u8; 4] = rumble.into();
+/// assert_eq!(encoded, data);
+///
+/// let rumble = Rumble::new(200.0, 0.5);
+/// let data: [u8; 4] = rumble.into();
+/// let decoded = Rumble::try_from(data).unwrap();
+/// assert!((decoded.high().frequency() - 200.0).abs() < 3.0);
+/// assert!((decoded.low().amplitude() - 0.5).abs() < 0.01);
+/// assert_eq!(<[u8; 4]>::from(decoded), data);
+/// ```
+///
+/// Rumble data of other sub-types, and of the low band frequency 0, is rejected
+/// rather than decoded into rumble encoded differently.
+/// ```
+/// use joycon_rs::prelude::*;
+/// use std::convert::TryFrom;
+///
+/// // Sub-type 1
+/// assert!(Rumble::try_from([0x01, 0xC9, 0x40, 0x40]).is_err());
+/// // Low band frequency 0
+/// assert!(Rumble::try_from([0x00, 0x01, 0x00, 0x40]).is_err());
+///
+/// // Anything else of the standard encoding round-trips
+/// for fields in (0..1u32 << 28).step_by(4099) {
+///     let data = ((1 << 30) | (fields << 2)).to_le_bytes();
+///     match Rumble::try_from(data) {
+///         Ok(rumble) => assert_eq!(<[u8; 4]>::from(rumble), data),
+///         Err(_) => assert_eq!(data[2] & 0x7F, 0),
+///     }
+/// }
+/// ```
+impl TryFrom<[u8; 4]> for Rumble {
+    type Error = InvalidRumbleData;
+
+    fn try_from(buf: [u8; 4]) -> Result<Self, Self::Error> {
+        let frame_type = buf[3] >> 6;
+        let sub_type = buf[0] & 0x03;
+        let lf_freq = buf[2] & 0x7F;
+        if frame_type != 1 || sub_type != 0 || lf_freq == 0 {
+            return Err(InvalidRumbleData::UnsupportedEncoding(buf));
+        }
+
+        let hf_freq = (buf[0] >> 2) + ((buf[1] & 0x01) << 6);
+        let hf_amp = buf[1] >> 1;
+        let lf_amp = (buf[3] & 0x3F) * 2 + (buf[2] >> 7);
+
+        let high = RumbleBand::decode((hf_freq + 0x60) as f32, hf_amp);
+        let low = RumbleBand::decode((lf_freq + 0x40) as f32, lf_amp);
+
+        Ok(Rumble { high, low })
+    }
+}
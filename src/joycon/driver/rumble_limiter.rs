@@ -0,0 +1,190 @@
+//! Safety layer for rumble.
+//!
+//! [`RumbleLimiter`] caps amplitudes and how long the actuators rumble hard.
+//! `SimpleJoyConDriver` and [`RumbleSequencer`] apply the default limiter unless opted out.
+//!
+//! [`RumbleLimiter`]: struct.RumbleLimiter.html
+//! [`RumbleSequencer`]: ../rumble_sequencer/struct.RumbleSequencer.html
+
+use super::*;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// What to do with amplitudes over the limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OnUnsafeAmplitude {
+    /// Saturate the amplitude to the limit.
+    Clamp,
+    /// Return `JoyConError::UnsafeRumble` without rumbling.
+    Reject,
+}
+
+/// Limit of the time rumbling at high amplitude.
+///
+/// While amplitudes over `amplitude` have been sent for more than `max_ratio` of `window`,
+/// they are reduced to `amplitude`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DutyCycle {
+    pub amplitude: f32,
+    pub window: Duration,
+    pub max_ratio: f32,
+}
+
+impl Default for DutyCycle {
+    fn default() -> Self {
+        DutyCycle {
+            amplitude: 0.6,
+            window: Duration::from_secs(10),
+            max_ratio: 0.5,
+        }
+    }
+}
+
+/// Limits amplitudes and duty cycles of rumble to protect the actuators.
+///
+/// The actuator keeps the last rumble until the next one,
+/// so the time from a high amplitude rumble to the next rumble under `DutyCycle::amplitude`
+/// counts toward the duty cycle.
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{*, rumble_limiter::*};
+///
+/// let mut limiter = RumbleLimiter::default();
+///
+/// let (left, _) = limiter.limit((Some(Rumble::new(300.0, 1.5)), None)).unwrap();
+/// assert_eq!(left.unwrap().amplitude(), Rumble::SAFE_AMPLITUDE);
+///
+/// limiter.on_unsafe = OnUnsafeAmplitude::Reject;
+/// assert!(limiter.limit((Some(Rumble::new(300.0, 1.5)), None)).is_err());
+/// ```
+///
+/// ```
+/// use joycon_rs::prelude::{*, rumble_limiter::*};
+/// use std::time::{Duration, Instant};
+///
+/// let mut limiter = RumbleLimiter::default();
+/// let start = Instant::now();
+/// let strong = (Some(Rumble::new(160.0, 0.9)), None);
+///
+/// limiter.limit_at(strong, start).unwrap();
+/// // 5 of 10 seconds rumbling strongly
+/// let (left, _) = limiter.limit_at(strong, start + Duration::from_secs(5)).unwrap();
+/// assert_eq!(left.unwrap().amplitude(), 0.6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RumbleLimiter {
+    /// Max amplitude of each band.
+    pub max_amplitude: f32,
+    pub on_unsafe: OnUnsafeAmplitude,
+    /// `None` doesn't limit duty cycles.
+    pub duty_cycle: Option<DutyCycle>,
+    /// Periods of high amplitude rumble. The last one may be ongoing.
+    high_periods: VecDeque<(Instant, Option<Instant>)>,
+}
+
+impl Default for RumbleLimiter {
+    fn default() -> Self {
+        RumbleLimiter::new(
+            Rumble::SAFE_AMPLITUDE,
+            OnUnsafeAmplitude::Clamp,
+            Some(DutyCycle::default()),
+        )
+    }
+}
+
+impl RumbleLimiter {
+    pub fn new(
+        max_amplitude: f32,
+        on_unsafe: OnUnsafeAmplitude,
+        duty_cycle: Option<DutyCycle>,
+    ) -> Self {
+        RumbleLimiter {
+            max_amplitude,
+            on_unsafe,
+            duty_cycle,
+            high_periods: VecDeque::new(),
+        }
+    }
+
+    /// Limit rumble about to be sent now.
+    pub fn limit(
+        &mut self,
+        rumble_l_r: (Option<Rumble>, Option<Rumble>),
+    ) -> JoyConResult<(Option<Rumble>, Option<Rumble>)> {
+        self.limit_at(rumble_l_r, Instant::now())
+    }
+
+    /// Limit rumble about to be sent at `now`.
+    pub fn limit_at(
+        &mut self,
+        rumble_l_r: (Option<Rumble>, Option<Rumble>),
+        now: Instant,
+    ) -> JoyConResult<(Option<Rumble>, Option<Rumble>)> {
+        let (left, right) = rumble_l_r;
+        let mut rumbles = [left, right];
+
+        for rumble in rumbles.iter_mut().flatten() {
+            if rumble.amplitude() > self.max_amplitude {
+                match self.on_unsafe {
+                    OnUnsafeAmplitude::Clamp => *rumble = saturate(*rumble, self.max_amplitude),
+                    OnUnsafeAmplitude::Reject => return Err(JoyConError::UnsafeRumble(*rumble)),
+                }
+            }
+        }
+
+        if let Some(duty_cycle) = self.duty_cycle {
+            let mut high = rumbles
+                .iter()
+                .flatten()
+                .any(|r| r.amplitude() > duty_cycle.amplitude);
+
+            let limit = duty_cycle.window.mul_f32(duty_cycle.max_ratio);
+            if high && self.high_duration(duty_cycle.window, now) >= limit {
+                rumbles
+                    .iter_mut()
+                    .flatten()
+                    .for_each(|r| *r = saturate(*r, duty_cycle.amplitude));
+                high = false;
+            }
+
+            match self.high_periods.back_mut() {
+                Some((_, end @ None)) if !high => *end = Some(now),
+                Some((_, None)) => {}
+                _ if high => self.high_periods.push_back((now, None)),
+                _ => {}
+            }
+        }
+
+        let [left, right] = rumbles;
+        Ok((left, right))
+    }
+
+    /// Time rumbling at high amplitude in the window until `now`.
+    fn high_duration(&mut self, window: Duration, now: Instant) -> Duration {
+        // `None` if the window begins before the origin of `Instant`
+        let window_start = now.checked_sub(window);
+        if let Some(window_start) = window_start {
+            self.high_periods
+                .retain(|&(_, end)| end.is_none_or(|end| end > window_start));
+        }
+
+        self.high_periods
+            .iter()
+            .map(|&(start, end)| {
+                let start = window_start.map_or(start, |window_start| start.max(window_start));
+                end.unwrap_or(now).saturating_duration_since(start)
+            })
+            .sum()
+    }
+}
+
+/// Saturate amplitudes of the bands.
+fn saturate(rumble: Rumble, max_amplitude: f32) -> Rumble {
+    let (high, low) = (rumble.high(), rumble.low());
+
+    Rumble::dual_band(
+        RumbleBand::new(high.frequency(), high.amplitude().min(max_amplitude)),
+        RumbleBand::new(low.frequency(), low.amplitude().min(max_amplitude)),
+    )
+}
@@ -0,0 +1,341 @@
+//! Play timelines of rumble frames.
+//!
+//! A [`RumblePattern`] consists of a [`RumbleTrack`] for each side,
+//! and a track consists of [`RumbleFrame`]s with [`Envelope`]s.
+//! The sequencer streams rumble packets to the device in the background
+//! and stops rumbling when the pattern ends or the playback is cancelled.
+//!
+//! # Usage
+//! ```no_run
+//! use joycon_rs::prelude::{*, rumble_sequencer::*};
+//! use std::sync::{Arc, Mutex};
+//! use std::time::Duration;
+//!
+//! // Vibration must be enabled, e.g. by `SimpleJoyConDriver::new`
+//! fn heartbeat(device: &Arc<Mutex<JoyConDevice>>) -> JoyConResult<()> {
+//!     let beat = RumbleTrack::new()
+//!         .frame_with_envelope(
+//!             Rumble::new(160.0, 0.8),
+//!             Duration::from_millis(120),
+//!             Envelope::new(Duration::from_millis(20), Duration::from_millis(80)),
+//!         )
+//!         .silence(Duration::from_millis(100))
+//!         .frame(Rumble::new(160.0, 0.5), Duration::from_millis(80))
+//!         .silence(Duration::from_millis(500));
+//!     let pattern = RumblePattern::new(Some(beat.clone()), Some(beat)).repeat(Repeat::Times(3));
+//!
+//!     RumbleSequencer::new(device).play(pattern).join()
+//! }
+//! ```
+//!
+//! [`RumbleSequencer`]: struct.RumbleSequencer.html
+//! [`RumblePattern`]: struct.RumblePattern.html
+//! [`RumbleTrack`]: struct.RumbleTrack.html
+//! [`RumbleFrame`]: struct.RumbleFrame.html
+//! [`Envelope`]: struct.Envelope.html
+
+use super::playback::Playback;
+use super::*;
+use std::time::{Duration, Instant};
+
+/// Amplitude envelope of a frame.
+/// The amplitude rises from 0 during `attack`, and falls to 0 during `decay` at the end of the frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Envelope {
+    pub attack: Duration,
+    pub decay: Duration,
+}
+
+impl Envelope {
+    pub fn new(attack: Duration, decay: Duration) -> Self {
+        Envelope { attack, decay }
+    }
+
+    /// Gain of the amplitude at `elapsed` in a frame lasting `duration`. 0.0 <= gain <= 1.0
+    pub fn gain(&self, elapsed: Duration, duration: Duration) -> f32 {
+        let attack = if elapsed < self.attack {
+            elapsed.as_secs_f32() / self.attack.as_secs_f32()
+        } else {
+            1.0
+        };
+
+        let remaining = duration.checked_sub(elapsed).unwrap_or_default();
+        let decay = if remaining < self.decay {
+            remaining.as_secs_f32() / self.decay.as_secs_f32()
+        } else {
+            1.0
+        };
+
+        attack.min(decay).clamp(0.0, 1.0)
+    }
+}
+
+/// A rumble lasting for a duration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RumbleFrame {
+    pub rumble: Rumble,
+    pub duration: Duration,
+    pub envelope: Envelope,
+}
+
+impl RumbleFrame {
+    /// Rumble at time `elapsed` in the frame, with the envelope applied.
+    pub fn rumble_at(&self, elapsed: Duration) -> Rumble {
+        let gain = self.envelope.gain(elapsed, self.duration);
+        let (high, low) = (self.rumble.high(), self.rumble.low());
+
+        Rumble::dual_band(
+            RumbleBand::new(high.frequency(), high.amplitude() * gain),
+            RumbleBand::new(low.frequency(), low.amplitude() * gain),
+        )
+    }
+}
+
+/// Timeline of rumble frames of one side.
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{*, rumble_sequencer::*};
+/// use std::time::Duration;
+///
+/// let track = RumbleTrack::new()
+///     .frame(Rumble::new(300.0, 0.5), Duration::from_millis(100))
+///     .silence(Duration::from_millis(50));
+///
+/// assert_eq!(track.duration(), Duration::from_millis(150));
+/// assert_eq!(track.rumble_at(Duration::from_millis(20)), Some(Rumble::new(300.0, 0.5)));
+/// assert_eq!(track.rumble_at(Duration::from_millis(120)), Some(Rumble::stop()));
+/// assert_eq!(track.rumble_at(Duration::from_millis(150)), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RumbleTrack {
+    frames: Vec<RumbleFrame>,
+}
+
+impl RumbleTrack {
+    pub fn new() -> Self {
+        RumbleTrack { frames: Vec::new() }
+    }
+
+    pub fn push_frame(&mut self, frame: RumbleFrame) {
+        self.frames.push(frame);
+    }
+
+    pub fn frames(&self) -> &Vec<RumbleFrame> {
+        &self.frames
+    }
+
+    /// Add a frame rumbling steadily.
+    pub fn frame(self, rumble: Rumble, duration: Duration) -> Self {
+        self.frame_with_envelope(rumble, duration, Envelope::default())
+    }
+
+    /// Add a frame with an envelope.
+    pub fn frame_with_envelope(
+        mut self,
+        rumble: Rumble,
+        duration: Duration,
+        envelope: Envelope,
+    ) -> Self {
+        self.push_frame(RumbleFrame {
+            rumble,
+            duration,
+            envelope,
+        });
+        self
+    }
+
+    /// Add a frame without rumble.
+    pub fn silence(self, duration: Duration) -> Self {
+        self.frame(Rumble::stop(), duration)
+    }
+
+    /// Total duration of the frames.
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|f| f.duration).sum()
+    }
+
+    /// Rumble at time `elapsed` from the beginning. `None` if the track has ended.
+    pub fn rumble_at(&self, elapsed: Duration) -> Option<Rumble> {
+        let mut start = Duration::from_millis(0);
+        for frame in &self.frames {
+            let end = start + frame.duration;
+            if elapsed < end {
+                return Some(frame.rumble_at(elapsed - start));
+            }
+            start = end;
+        }
+
+        None
+    }
+}
+
+/// How many times a pattern is played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Repeat {
+    Once,
+    Times(u32),
+    Forever,
+}
+
+/// Tracks of the left and right side, played simultaneously.
+///
+/// A side without track doesn't rumble.
+/// A track shorter than the other stops rumbling until the pattern loops.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RumblePattern {
+    pub left: Option<RumbleTrack>,
+    pub right: Option<RumbleTrack>,
+    pub repeat: Repeat,
+}
+
+impl RumblePattern {
+    /// Pattern played once.
+    pub fn new(left: Option<RumbleTrack>, right: Option<RumbleTrack>) -> Self {
+        RumblePattern {
+            left,
+            right,
+            repeat: Repeat::Once,
+        }
+    }
+
+    /// Set how many times the pattern is played.
+    pub fn repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Duration of a loop.
+    pub fn loop_duration(&self) -> Duration {
+        let left = self.left.as_ref().map(RumbleTrack::duration);
+        let right = self.right.as_ref().map(RumbleTrack::duration);
+
+        left.unwrap_or_default().max(right.unwrap_or_default())
+    }
+
+    /// Rumble of each side at time `elapsed` from the beginning. `None` if the pattern has ended.
+    pub fn rumble_at(&self, elapsed: Duration) -> Option<(Option<Rumble>, Option<Rumble>)> {
+        let loop_duration = self.loop_duration();
+        if loop_duration == Duration::from_millis(0) {
+            return None;
+        }
+
+        let loop_count = (elapsed.as_nanos() / loop_duration.as_nanos()) as u64;
+        let finished = match self.repeat {
+            Repeat::Once => loop_count >= 1,
+            Repeat::Times(times) => loop_count >= times as u64,
+            Repeat::Forever => false,
+        };
+        if finished {
+            return None;
+        }
+
+        let elapsed_in_loop =
+            Duration::from_nanos((elapsed.as_nanos() % loop_duration.as_nanos()) as u64);
+        let rumble_at = |track: &Option<RumbleTrack>| {
+            track
+                .as_ref()
+                .map(|t| t.rumble_at(elapsed_in_loop).unwrap_or_else(Rumble::stop))
+        };
+
+        Some((rumble_at(&self.left), rumble_at(&self.right)))
+    }
+}
+
+/// Plays rumble patterns on a device in the background.
+///
+/// Rumble packets are sent through the output scheduler of the device,
+/// so the sequencer can be used alongside drivers and input report modes on the same device.
+/// They take the packet number from the device and update its rumble status,
+/// so output reports of the drivers carry the rumble being played.
+///
+/// Rumble is limited by the default [`RumbleLimiter`] unless [`set_limiter`] opts out.
+///
+/// # Notice
+/// Vibration must be enabled by a driver beforehand.
+/// `SimpleJoyConDriver` enables it on construction.
+///
+/// [`RumbleLimiter`]: ../rumble_limiter/struct.RumbleLimiter.html
+/// [`set_limiter`]: #method.set_limiter
+#[derive(Debug, Clone)]
+pub struct RumbleSequencer {
+    device: Arc<Mutex<JoyConDevice>>,
+    cadence: Duration,
+    limiter: Option<RumbleLimiter>,
+}
+
+impl RumbleSequencer {
+    /// Default interval of sending rumble packets.
+    pub const DEFAULT_CADENCE: Duration = Duration::from_millis(20);
+
+    pub fn new(device: &Arc<Mutex<JoyConDevice>>) -> Self {
+        Self::with_cadence(device, Self::DEFAULT_CADENCE)
+    }
+
+    /// Constructs a sequencer sending rumble packets at the interval.
+    pub fn with_cadence(device: &Arc<Mutex<JoyConDevice>>, cadence: Duration) -> Self {
+        RumbleSequencer {
+            device: Arc::clone(device),
+            cadence,
+            limiter: Some(RumbleLimiter::default()),
+        }
+    }
+
+    pub fn cadence(&self) -> Duration {
+        self.cadence
+    }
+
+    pub fn limiter(&self) -> Option<&RumbleLimiter> {
+        self.limiter.as_ref()
+    }
+
+    /// Set the safety layer applied to each rumble packet of the following playbacks.
+    /// `None` opts out of limiting rumble.
+    pub fn set_limiter(&mut self, limiter: Option<RumbleLimiter>) {
+        self.limiter = limiter;
+    }
+
+    /// Start playing the pattern in the background.
+    pub fn play(&self, pattern: RumblePattern) -> RumblePlayback {
+        let device = Arc::clone(&self.device);
+        let cadence = self.cadence;
+        let mut limiter = self.limiter.clone();
+
+        Playback::spawn(move |cancellation| {
+            let send = |rumble_l_r| JoyConDevice::rumble_paced(&device, rumble_l_r);
+
+            let start = Instant::now();
+            let mut next_tick = start;
+            let result = loop {
+                if cancellation.is_cancelled() {
+                    break Ok(());
+                }
+
+                let rumble_l_r = match pattern.rumble_at(start.elapsed()) {
+                    Some(rumble_l_r) => rumble_l_r,
+                    None => break Ok(()),
+                };
+                let rumble_l_r = match limiter.as_mut().map(|l| l.limit(rumble_l_r)) {
+                    Some(Ok(rumble_l_r)) => rumble_l_r,
+                    Some(Err(e)) => break Err(e),
+                    None => rumble_l_r,
+                };
+                if let Err(e) = send(rumble_l_r) {
+                    break Err(e);
+                }
+
+                next_tick += cadence;
+                cancellation.sleep_until(next_tick);
+            };
+
+            // Stop rumbling whether the pattern ends or not
+            let stop = send((Some(Rumble::stop()), Some(Rumble::stop())));
+
+            result.and(stop.map(|_| ()))
+        })
+    }
+}
+
+/// Handle of a pattern being played.
+/// Dropping the handle, or cancelling it, stops playing and rumbling.
+pub type RumblePlayback = Playback;
@@ -35,16 +35,24 @@ pub struct SimpleJoyConDriver {
     pub joycon: Arc<Mutex<JoyConDevice>>,
     /// rotation of controller
     pub rotation: Rotation,
-    rumble: (Option<Rumble>, Option<Rumble>),
     enabled_features: HashSet<JoyConFeature>,
     valid_reply: bool,
-    /// Increment by 1 for each packet sent. It loops in 0x0 - 0xF range.
-    global_packet_number: GlobalPacketNumber,
+    retry_policy: RetryPolicy,
+    rumble_limiter: Option<RumbleLimiter>,
+    stop_rumble_on_drop: bool,
 }
 
 impl SimpleJoyConDriver {
     /// Constructs a new `SimpleJoyConDriver`.
     pub fn new(joycon: &Arc<Mutex<JoyConDevice>>) -> JoyConResult<Self> {
+        Self::with_retry_policy(joycon, RetryPolicy::default())
+    }
+
+    /// Constructs a new `SimpleJoyConDriver` with timeout and retry policy for sub-commands.
+    pub fn with_retry_policy(
+        joycon: &Arc<Mutex<JoyConDevice>>,
+        retry_policy: RetryPolicy,
+    ) -> JoyConResult<Self> {
         // joycon.set_blocking_mode(true);
         // joycon.set_blocking_mode(false);
 
@@ -55,7 +63,6 @@ impl SimpleJoyConDriver {
         let mut driver = Self {
             joycon: Arc::clone(joycon),
             rotation: Rotation::Portrait,
-            rumble: (None, None),
             enabled_features,
             valid_reply: {
                 let device = match joycon.lock() {
@@ -65,7 +72,9 @@ impl SimpleJoyConDriver {
                 .device_type();
                 !matches!(device, JoyConDeviceType::ProCon)
             },
-            global_packet_number: GlobalPacketNumber::default(),
+            retry_policy,
+            rumble_limiter: Some(RumbleLimiter::default()),
+            stop_rumble_on_drop: true,
         };
 
         let check_reply = {
@@ -85,7 +94,26 @@ impl SimpleJoyConDriver {
         Ok(driver)
     }
 
-    pub fn joycon(&self) -> MutexGuard<JoyConDevice> {
+    pub fn rumble_limiter(&self) -> Option<&RumbleLimiter> {
+        self.rumble_limiter.as_ref()
+    }
+
+    /// Set the safety layer of rumble.
+    /// `None` opts out of limiting rumble.
+    pub fn set_rumble_limiter(&mut self, limiter: Option<RumbleLimiter>) {
+        self.rumble_limiter = limiter;
+    }
+
+    pub fn stop_rumble_on_drop(&self) -> bool {
+        self.stop_rumble_on_drop
+    }
+
+    /// Whether to stop rumbling when the driver is dropped. `true` by default.
+    pub fn set_stop_rumble_on_drop(&mut self, stop: bool) {
+        self.stop_rumble_on_drop = stop;
+    }
+
+    pub fn joycon(&self) -> MutexGuard<'_, JoyConDevice> {
         // todo error handling
         match self.joycon.lock() {
             Ok(joycon) => joycon,
@@ -103,9 +131,16 @@ impl JoyConDriver for SimpleJoyConDriver {
         self.valid_reply = valid;
     }
 
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
     fn write(&self, data: &[u8]) -> JoyConResult<usize> {
-        let joycon = self.joycon();
-        joycon.write(data)
+        JoyConDevice::write_paced(&self.joycon, data)
     }
 
     fn read(&self, buf: &mut [u8]) -> JoyConResult<usize> {
@@ -116,20 +151,39 @@ impl JoyConDriver for SimpleJoyConDriver {
         self.joycon().read_timeout(buf, timeout)
     }
 
+    /// The counter is kept by the device, and shared with other senders.
     fn global_packet_number(&self) -> u8 {
-        self.global_packet_number.into()
+        self.joycon().global_packet_number().into()
     }
 
     fn increase_global_packet_number(&mut self) {
-        self.global_packet_number = self.global_packet_number.next();
+        self.joycon().take_global_packet_number();
+    }
+
+    fn send_command_raw(
+        &mut self,
+        command: u8,
+        sub_command: u8,
+        data: &[u8],
+    ) -> JoyConResult<usize> {
+        self.send_output_report(command, sub_command, data)
     }
 
+    fn send_output_report(&self, command: u8, sub_command: u8, data: &[u8]) -> JoyConResult<usize> {
+        JoyConDevice::send_paced(&self.joycon, command, sub_command, data)
+    }
+
+    /// The rumble status is kept by the device, and shared with other senders.
     fn set_rumble_status(&mut self, rumble_l_r: (Option<Rumble>, Option<Rumble>)) {
-        self.rumble = rumble_l_r;
+        self.joycon().set_rumble_status(rumble_l_r);
     }
 
     fn get_rumble_status(&self) -> (Option<Rumble>, Option<Rumble>) {
-        self.rumble
+        self.joycon().rumble_status()
+    }
+
+    fn rumble_limiter_mut(&mut self) -> Option<&mut RumbleLimiter> {
+        self.rumble_limiter.as_mut()
     }
 
     fn enable_feature(&mut self, feature: JoyConFeature) -> JoyConResult<()> {
@@ -147,6 +201,11 @@ impl JoyConDriver for SimpleJoyConDriver {
             }
         }
 
+        // Keep only the latest config of IMU
+        if let JoyConFeature::IMUFeature(_) = feature {
+            self.enabled_features
+                .retain(|f| !matches!(f, JoyConFeature::IMUFeature(_)));
+        }
         self.enabled_features.insert(feature);
 
         Ok(())
@@ -160,3 +219,24 @@ impl JoyConDriver for SimpleJoyConDriver {
         vec![Arc::clone(&self.joycon)]
     }
 }
+
+impl Drop for SimpleJoyConDriver {
+    /// Stop rumbling unless opted out by [`set_stop_rumble_on_drop`].
+    ///
+    /// The rumble status is kept by the device,
+    /// so rumble played by a `RumbleSequencer` on the device is stopped as well.
+    ///
+    /// [`set_stop_rumble_on_drop`]: #method.set_stop_rumble_on_drop
+    fn drop(&mut self) {
+        let (left, right) = self.get_rumble_status();
+        let rumbling = [left, right]
+            .iter()
+            .flatten()
+            .any(|rumble| rumble.amplitude() > 0.0);
+
+        if self.stop_rumble_on_drop && rumbling {
+            self.set_rumble_status((Some(Rumble::stop()), Some(Rumble::stop())));
+            let _ = self.send_command_raw(Command::Rumble as u8, 0, &[]);
+        }
+    }
+}
@@ -0,0 +1,474 @@
+//! Motion gestures from the IMU(6-Axis sensor).
+//!
+//! Directions assume the controller is held like lying flat with buttons up.
+//! See [the IMU module] for axes.
+//!
+//! # Usage
+//! [`GestureRecognizer`] expects samples of Joy-Con (R) aligned with Joy-Con (L).
+//! ```no_run
+//! use joycon_rs::prelude::{*, imu::*, gesture::*};
+//! use std::time::Instant;
+//!
+//! fn print_gestures(standard_full_mode: &StandardFullMode<SimpleJoyConDriver>, calibrator: &mut IMUCalibrator) -> JoyConResult<()> {
+//!     calibrator.set_aligned(true);
+//!     let mut recognizer = GestureRecognizer::new(GestureThresholds::default());
+//!
+//!     loop {
+//!         let report = standard_full_mode.read_input_report()?;
+//!         let samples = report.extra.samples(calibrator);
+//!
+//!         for event in recognizer.update_samples(&samples, Instant::now()) {
+//!             match event.gesture {
+//!                 Gesture::Flick { direction } => println!("Flicked to {:?}", direction),
+//!                 gesture => println!("{:?}", gesture),
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`GestureRecognizer`]: struct.GestureRecognizer.html
+//! [the IMU module]: ../imu/index.html#axes
+
+use super::imu::{IMUSample, Vector3};
+use std::collections::{HashMap, VecDeque};
+use std::mem::Discriminant;
+use std::time::{Duration, Instant};
+
+/// Direction of flicks and tilts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GestureDirection {
+    /// Toward the shoulder buttons going up.
+    Up,
+    /// Toward the shoulder buttons going down.
+    Down,
+    Left,
+    Right,
+}
+
+/// Direction of twists seen from behind the controller, looking toward the shoulder buttons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TwistDirection {
+    Clockwise,
+    Counterclockwise,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// Shaken back and forth `count` times.
+    Shake { count: u32 },
+    /// Quick short rotation.
+    Flick { direction: GestureDirection },
+    /// Sharp knock without rotation.
+    Tap,
+    /// Tilted past the threshold. `angle` is in degrees.
+    Tilt {
+        direction: GestureDirection,
+        angle: f32,
+    },
+    /// Back from a tilt.
+    TiltReleased { direction: GestureDirection },
+    /// Long rotation. `peak_speed` is in dps.
+    Swing { peak_speed: f32, duration: Duration },
+    /// Rotation around the long axis. `angle` is in degrees.
+    Twist {
+        direction: TwistDirection,
+        angle: f32,
+    },
+}
+
+/// A recognized gesture and when it happened.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureEvent {
+    pub gesture: Gesture,
+    pub timestamp: Instant,
+}
+
+/// Thresholds of gestures.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureThresholds {
+    /// Linear acceleration counted as a stroke of shaking, in G.
+    pub shake_acceleration: f32,
+    /// Strokes in opposite directions needed for a shake.
+    pub shake_count: u32,
+    /// Time the strokes of a shake must happen within.
+    pub shake_window: Duration,
+    /// Peak angular speed of flicks in dps.
+    pub flick_speed: f32,
+    /// Rotations longer than this are swings.
+    pub flick_max_duration: Duration,
+    /// Linear acceleration of taps in G.
+    pub tap_acceleration: f32,
+    /// Max angular speed during taps in dps.
+    pub tap_max_angular_speed: f32,
+    /// Time without motion needed before taps.
+    pub tap_quiet: Duration,
+    /// Max length of the spike of taps.
+    pub tap_max_duration: Duration,
+    /// Angle of tilts in degrees.
+    pub tilt_angle: f32,
+    /// Tilts are released under `tilt_angle - tilt_hysteresis` degrees.
+    pub tilt_hysteresis: f32,
+    /// Peak angular speed of swings in dps.
+    pub swing_speed: f32,
+    /// Angle around the long axis of twists in degrees.
+    pub twist_angle: f32,
+    /// Angular speed where a rotation begins and ends, in dps.
+    pub rotation_speed: f32,
+    /// Time a gesture of the same kind is ignored after one.
+    /// Releases of tilts are never ignored, so each tilt is released.
+    pub cooldown: Duration,
+}
+
+impl Default for GestureThresholds {
+    fn default() -> Self {
+        GestureThresholds {
+            shake_acceleration: 1.0,
+            shake_count: 4,
+            shake_window: Duration::from_millis(800),
+            flick_speed: 400.0,
+            flick_max_duration: Duration::from_millis(200),
+            tap_acceleration: 1.5,
+            tap_max_angular_speed: 100.0,
+            tap_quiet: Duration::from_millis(150),
+            tap_max_duration: Duration::from_millis(30),
+            tilt_angle: 45.0,
+            tilt_hysteresis: 10.0,
+            swing_speed: 300.0,
+            twist_angle: 60.0,
+            rotation_speed: 120.0,
+            cooldown: Duration::from_millis(300),
+        }
+    }
+}
+
+/// Rotation in progress.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rotation {
+    start: Instant,
+    peak_speed: f32,
+    peak_velocity: Vector3,
+    /// Integral of angular velocity in degrees.
+    angle: Vector3,
+}
+
+/// Recognizes gestures of a controller from IMU samples.
+///
+/// Samples should be corrected by [`IMUCalibrator`], and Joy-Con (R) should be aligned.
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{imu::*, gesture::*};
+/// use std::time::{Duration, Instant};
+///
+/// let mut recognizer = GestureRecognizer::new(GestureThresholds::default());
+/// let mut now = Instant::now();
+/// let mut events = Vec::new();
+///
+/// // Flick to the left
+/// for i in 0..30 {
+///     let speed = if (10..20).contains(&i) { 600.0 } else { 0.0 };
+///     let sample = IMUSample {
+///         acceleration: Vector3::new(0.0, 0.0, 1.0),
+///         angular_velocity: Vector3::new(0.0, 0.0, speed),
+///     };
+///     now += Duration::from_millis(5);
+///     events.extend(recognizer.update(&sample, now));
+/// }
+///
+/// assert_eq!(events.len(), 1);
+/// assert_eq!(events[0].gesture, Gesture::Flick { direction: GestureDirection::Left });
+/// ```
+///
+/// [`IMUCalibrator`]: ../imu/struct.IMUCalibrator.html
+#[derive(Debug, Clone)]
+pub struct GestureRecognizer {
+    pub thresholds: GestureThresholds,
+    /// Period between samples passed to `update_samples`.
+    pub sample_period: Duration,
+    previous: Option<Instant>,
+    gravity: Option<Vector3>,
+    /// Strokes of shaking, and their directions.
+    strokes: VecDeque<(Instant, Vector3)>,
+    last_motion: Option<Instant>,
+    pending_tap: Option<Instant>,
+    rotation: Option<Rotation>,
+    tilt: Option<GestureDirection>,
+    /// Last gesture of each kind, for the cooldown.
+    last_events: HashMap<Discriminant<Gesture>, Instant>,
+}
+
+impl GestureRecognizer {
+    /// Smoothing of gravity from the accelerometer.
+    const GRAVITY_SMOOTHING: f32 = 0.05;
+    /// Linear acceleration counted as motion for taps, in G.
+    const MOTION_ACCELERATION: f32 = 0.3;
+    /// Deviation from 1G where gravity can be measured, in G.
+    const STEADY_ACCELERATION: f32 = 0.2;
+
+    pub fn new(thresholds: GestureThresholds) -> Self {
+        GestureRecognizer {
+            thresholds,
+            sample_period: IMUSample::PERIOD,
+            previous: None,
+            gravity: None,
+            strokes: VecDeque::new(),
+            last_motion: None,
+            pending_tap: None,
+            rotation: None,
+            tilt: None,
+            last_events: HashMap::new(),
+        }
+    }
+
+    /// Update with samples of a report. The latest is dated `received`, and older ones `sample_period` apart.
+    pub fn update_samples(
+        &mut self,
+        samples: &[IMUSample],
+        received: Instant,
+    ) -> Vec<GestureEvent> {
+        let period = self.sample_period;
+
+        samples
+            .iter()
+            .enumerate()
+            .rev()
+            .flat_map(|(age, sample)| {
+                let timestamp = received
+                    .checked_sub(period * age as u32)
+                    .unwrap_or(received);
+                self.update(sample, timestamp)
+            })
+            .collect()
+    }
+
+    /// Update with a sample measured at `timestamp`.
+    pub fn update(&mut self, sample: &IMUSample, timestamp: Instant) -> Vec<GestureEvent> {
+        let dt = self
+            .previous
+            .map(|previous| timestamp.saturating_duration_since(previous))
+            .unwrap_or(self.sample_period);
+        self.previous = Some(timestamp);
+
+        // Gravity is followed only while the accelerometer measures about 1G
+        let accel = sample.acceleration;
+        let steady = (accel.magnitude() - 1.0).abs() < Self::STEADY_ACCELERATION;
+        let gravity = match self.gravity {
+            Some(gravity) if steady => gravity + (accel - gravity).scale(Self::GRAVITY_SMOOTHING),
+            Some(gravity) => gravity,
+            None => accel,
+        };
+        self.gravity = Some(gravity);
+        let linear = accel - gravity;
+
+        let mut gestures = Vec::new();
+        gestures.extend(self.shake(linear, timestamp));
+        gestures.extend(self.tap(linear, sample.angular_velocity, timestamp));
+        if steady {
+            gestures.extend(self.tilt(gravity, timestamp));
+        }
+        gestures.extend(self.rotate(sample.angular_velocity, dt, timestamp));
+
+        gestures
+            .into_iter()
+            .map(|gesture| GestureEvent { gesture, timestamp })
+            .collect()
+    }
+
+    fn shake(&mut self, linear: Vector3, timestamp: Instant) -> Option<Gesture> {
+        let thresholds = self.thresholds;
+
+        while let Some(&(start, _)) = self.strokes.front() {
+            if timestamp.saturating_duration_since(start) > thresholds.shake_window {
+                self.strokes.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        if linear.magnitude() < thresholds.shake_acceleration {
+            return None;
+        }
+
+        // A stroke is acceleration against the previous one
+        let reversed = self
+            .strokes
+            .back()
+            .is_none_or(|(_, direction)| direction.dot(&linear) < 0.0);
+        if reversed {
+            self.strokes.push_back((timestamp, linear));
+        }
+
+        let count = self.strokes.len() as u32;
+        let shake = Gesture::Shake { count };
+        if count >= thresholds.shake_count && !self.cooling_down(&shake, timestamp) {
+            self.strokes.clear();
+            return self.emit(shake, timestamp);
+        }
+
+        None
+    }
+
+    fn tap(&mut self, linear: Vector3, gyro: Vector3, timestamp: Instant) -> Option<Gesture> {
+        let thresholds = self.thresholds;
+        let magnitude = linear.magnitude();
+
+        // A tap is confirmed when the spike ends soon
+        if let Some(start) = self.pending_tap {
+            if timestamp.saturating_duration_since(start) > thresholds.tap_max_duration {
+                self.pending_tap = None;
+            } else if magnitude < Self::MOTION_ACCELERATION {
+                self.pending_tap = None;
+                return self.emit(Gesture::Tap, timestamp);
+            }
+        }
+
+        let quiet = self
+            .last_motion
+            .is_none_or(|last| timestamp.saturating_duration_since(last) >= thresholds.tap_quiet);
+        if magnitude >= Self::MOTION_ACCELERATION {
+            self.last_motion = Some(timestamp);
+        }
+
+        if magnitude >= thresholds.tap_acceleration
+            && gyro.magnitude() < thresholds.tap_max_angular_speed
+            && quiet
+            && !self.cooling_down(&Gesture::Tap, timestamp)
+        {
+            self.pending_tap = Some(timestamp);
+        }
+
+        None
+    }
+
+    fn tilt(&mut self, gravity: Vector3, timestamp: Instant) -> Option<Gesture> {
+        let thresholds = self.thresholds;
+        let up = gravity.normalized()?;
+
+        // Angles the front end and the right side went down
+        let forward = (-up.x).clamp(-1.0, 1.0).asin().to_degrees();
+        let right = up.y.clamp(-1.0, 1.0).asin().to_degrees();
+
+        let angle_to = |direction| match direction {
+            GestureDirection::Down => forward,
+            GestureDirection::Up => -forward,
+            GestureDirection::Right => right,
+            GestureDirection::Left => -right,
+        };
+
+        if let Some(direction) = self.tilt {
+            if angle_to(direction) < thresholds.tilt_angle - thresholds.tilt_hysteresis {
+                self.tilt = None;
+                return Some(Gesture::TiltReleased { direction });
+            }
+            return None;
+        }
+
+        let (direction, angle) = [
+            GestureDirection::Up,
+            GestureDirection::Down,
+            GestureDirection::Left,
+            GestureDirection::Right,
+        ]
+        .iter()
+        .map(|&direction| (direction, angle_to(direction)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        if angle >= thresholds.tilt_angle {
+            let tilt = self.emit(Gesture::Tilt { direction, angle }, timestamp)?;
+            self.tilt = Some(direction);
+            return Some(tilt);
+        }
+
+        None
+    }
+
+    fn rotate(&mut self, gyro: Vector3, dt: Duration, timestamp: Instant) -> Option<Gesture> {
+        let thresholds = self.thresholds;
+        let speed = gyro.magnitude();
+
+        if speed >= thresholds.rotation_speed {
+            let rotation = self.rotation.get_or_insert(Rotation {
+                start: timestamp,
+                peak_speed: 0.0,
+                peak_velocity: Vector3::default(),
+                angle: Vector3::default(),
+            });
+            rotation.angle = rotation.angle + gyro.scale(dt.as_secs_f32());
+            if speed > rotation.peak_speed {
+                rotation.peak_speed = speed;
+                rotation.peak_velocity = gyro;
+            }
+            return None;
+        }
+
+        let rotation = self.rotation.take()?;
+        let duration = timestamp.saturating_duration_since(rotation.start);
+        let angle = rotation.angle;
+
+        // Around the long axis
+        if angle.x.abs() >= thresholds.twist_angle
+            && angle.x.abs() > angle.y.abs().max(angle.z.abs())
+        {
+            let direction = if angle.x > 0.0 {
+                TwistDirection::Clockwise
+            } else {
+                TwistDirection::Counterclockwise
+            };
+            let twist = Gesture::Twist {
+                direction,
+                angle: angle.x.abs(),
+            };
+            return self.emit(twist, timestamp);
+        }
+
+        if duration <= thresholds.flick_max_duration {
+            if rotation.peak_speed < thresholds.flick_speed {
+                return None;
+            }
+
+            let peak = rotation.peak_velocity;
+            let direction = if peak.z.abs() >= peak.y.abs() {
+                if peak.z > 0.0 {
+                    GestureDirection::Left
+                } else {
+                    GestureDirection::Right
+                }
+            } else if peak.y > 0.0 {
+                GestureDirection::Down
+            } else {
+                GestureDirection::Up
+            };
+            return self.emit(Gesture::Flick { direction }, timestamp);
+        }
+
+        if rotation.peak_speed >= thresholds.swing_speed {
+            let swing = Gesture::Swing {
+                peak_speed: rotation.peak_speed,
+                duration,
+            };
+            return self.emit(swing, timestamp);
+        }
+
+        None
+    }
+
+    fn cooling_down(&self, gesture: &Gesture, timestamp: Instant) -> bool {
+        self.last_events
+            .get(&std::mem::discriminant(gesture))
+            .is_some_and(|&last| {
+                timestamp.saturating_duration_since(last) < self.thresholds.cooldown
+            })
+    }
+
+    /// `gesture` unless one of the same kind happened within the cooldown.
+    fn emit(&mut self, gesture: Gesture, timestamp: Instant) -> Option<Gesture> {
+        if self.cooling_down(&gesture, timestamp) {
+            return None;
+        }
+
+        self.last_events
+            .insert(std::mem::discriminant(&gesture), timestamp);
+        Some(gesture)
+    }
+}
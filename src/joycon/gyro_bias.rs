@@ -0,0 +1,375 @@
+//! Continuous gyroscope bias calibration.
+//!
+//! Gyroscopes of Joy-Cons drift even with the calibration stored in the device.
+//! [`GyroBiasEstimator`] detects that a controller is at rest, and learns the bias of the gyroscope meanwhile.
+//! [`GyroBiasTracker`] keeps an estimator per serial number of controllers.
+//!
+//! # Usage
+//! Correct samples before filters such as orientation and pointers.
+//! ```no_run
+//! use joycon_rs::prelude::{*, imu::*, gyro_bias::*};
+//! use std::time::Duration;
+//!
+//! fn correct(
+//!     standard_full_mode: &StandardFullMode<SimpleJoyConDriver>,
+//!     calibrator: &IMUCalibrator,
+//!     tracker: &mut GyroBiasTracker,
+//! ) -> JoyConResult<()> {
+//!     let serial_number = standard_full_mode.driver().joycon().serial_number().to_string();
+//!     // Put the controller down for 3 seconds
+//!     tracker.calibrate_now(&serial_number, Duration::from_secs(3));
+//!
+//!     loop {
+//!         let report = standard_full_mode.read_input_report()?;
+//!         let samples = tracker.correct_samples(&serial_number, report.extra.samples(calibrator));
+//!         println!("{:?} dps, bias {:?}", samples[0].angular_velocity, tracker.bias(&serial_number));
+//!     }
+//! }
+//! ```
+//!
+//! [`GyroBiasEstimator`]: struct.GyroBiasEstimator.html
+//! [`GyroBiasTracker`]: struct.GyroBiasTracker.html
+
+use super::imu::{IMUSample, Vector3};
+use super::*;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+/// Thresholds to decide that a controller is at rest.
+///
+/// The controller is at rest while, over the last `window` samples,
+/// the variance of the magnitude of acceleration is under `accel_variance`,
+/// the variance of angular velocity is under `gyro_variance`,
+/// and the mean angular velocity is slower than `max_angular_velocity`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StationaryDetector {
+    /// Number of samples.
+    pub window: usize,
+    /// In G².
+    pub accel_variance: f32,
+    /// Sum of the variances of the axes in dps².
+    pub gyro_variance: f32,
+    /// In dps.
+    pub max_angular_velocity: f32,
+}
+
+impl Default for StationaryDetector {
+    fn default() -> Self {
+        StationaryDetector {
+            window: 40,
+            accel_variance: 0.0004,
+            gyro_variance: 1.0,
+            max_angular_velocity: 5.0,
+        }
+    }
+}
+
+impl StationaryDetector {
+    /// Whether the samples are at rest. `false` if there are fewer than `window` samples.
+    pub fn is_stationary<'a, I>(&self, samples: I) -> bool
+    where
+        I: IntoIterator<Item = &'a IMUSample>,
+        I::IntoIter: Clone,
+    {
+        let samples = samples.into_iter();
+        let count = samples.clone().count();
+        if count == 0 || count < self.window {
+            return false;
+        }
+        let n = count as f32;
+
+        let accel_mean = samples
+            .clone()
+            .map(|s| s.acceleration.magnitude())
+            .sum::<f32>()
+            / n;
+        let accel_variance = samples
+            .clone()
+            .map(|s| (s.acceleration.magnitude() - accel_mean).powi(2))
+            .sum::<f32>()
+            / n;
+
+        let gyro_mean = samples
+            .clone()
+            .fold(Vector3::default(), |sum, s| sum + s.angular_velocity)
+            .scale(1.0 / n);
+        let gyro_variance = samples
+            .map(|s| {
+                let d = s.angular_velocity - gyro_mean;
+                d.dot(&d)
+            })
+            .sum::<f32>()
+            / n;
+
+        accel_variance < self.accel_variance
+            && gyro_variance < self.gyro_variance
+            && gyro_mean.magnitude() < self.max_angular_velocity
+    }
+}
+
+/// Estimated bias of a gyroscope.
+#[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GyroBias {
+    /// In dps. Subtracted from angular velocities.
+    pub bias: Vector3,
+    /// From 0.0 (unknown) to 1.0 (calibrated explicitly).
+    pub confidence: f32,
+}
+
+/// Explicit calibration in progress.
+#[derive(Debug, Clone, PartialEq)]
+struct Calibration {
+    duration: Duration,
+    elapsed: Duration,
+    sum: Vector3,
+    count: u32,
+}
+
+impl Calibration {
+    fn new(duration: Duration) -> Self {
+        Calibration {
+            duration,
+            elapsed: Duration::from_secs(0),
+            sum: Vector3::default(),
+            count: 0,
+        }
+    }
+}
+
+/// Estimates the bias of the gyroscope of a controller.
+///
+/// While the controller is at rest, the bias approaches the mean angular velocity
+/// with the time constant `time_constant`.
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{imu::*, gyro_bias::*};
+/// use std::time::Duration;
+///
+/// let mut estimator = GyroBiasEstimator::new();
+/// let at_rest = IMUSample {
+///     acceleration: Vector3::new(0.0, 0.0, 1.0),
+///     angular_velocity: Vector3::new(1.5, -0.5, 0.25),
+/// };
+///
+/// // Learns the bias automatically
+/// for _ in 0..4000 {
+///     estimator.correct(at_rest);
+/// }
+/// let bias = estimator.bias();
+/// assert!((bias.bias - at_rest.angular_velocity).magnitude() < 0.01);
+/// assert!(bias.confidence > 0.9);
+///
+/// // Calibrate explicitly
+/// estimator.calibrate_now(Duration::from_secs(1));
+/// for _ in 0..200 {
+///     estimator.correct(at_rest);
+/// }
+/// assert_eq!(estimator.calibration_progress(), None);
+/// assert_eq!(estimator.bias().confidence, 1.0);
+/// assert!(estimator.correct(at_rest).angular_velocity.magnitude() < 1e-5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GyroBiasEstimator {
+    pub detector: StationaryDetector,
+    /// How fast the bias follows while at rest.
+    pub time_constant: Duration,
+    /// Time each sample stands for, at rest and during calibration.
+    pub sample_period: Duration,
+    recent: VecDeque<IMUSample>,
+    bias: GyroBias,
+    stationary: bool,
+    calibration: Option<Calibration>,
+}
+
+impl Default for GyroBiasEstimator {
+    fn default() -> Self {
+        GyroBiasEstimator::new()
+    }
+}
+
+impl GyroBiasEstimator {
+    pub fn new() -> Self {
+        GyroBiasEstimator {
+            detector: StationaryDetector::default(),
+            time_constant: Duration::from_secs(2),
+            sample_period: IMUSample::PERIOD,
+            recent: VecDeque::new(),
+            bias: GyroBias::default(),
+            stationary: false,
+            calibration: None,
+        }
+    }
+
+    /// Current estimate.
+    pub fn bias(&self) -> GyroBias {
+        self.bias
+    }
+
+    /// Replace the estimate, e.g. with a saved one.
+    pub fn set_bias(&mut self, bias: GyroBias) {
+        self.bias = bias;
+    }
+
+    /// Whether the controller was at rest at the last sample.
+    pub fn is_stationary(&self) -> bool {
+        self.stationary
+    }
+
+    /// Start calibration while the controller is put down.
+    ///
+    /// It averages angular velocities at rest for `duration`, and starts over when the controller moves.
+    pub fn calibrate_now(&mut self, duration: Duration) {
+        self.calibration = Some(Calibration::new(duration));
+    }
+
+    pub fn cancel_calibration(&mut self) {
+        self.calibration = None;
+    }
+
+    /// Progress of explicit calibration from 0.0 to 1.0. `None` unless calibrating.
+    pub fn calibration_progress(&self) -> Option<f32> {
+        self.calibration.as_ref().map(|calibration| {
+            if calibration.duration.as_secs_f32() > 0.0 {
+                (calibration.elapsed.as_secs_f32() / calibration.duration.as_secs_f32()).min(1.0)
+            } else {
+                1.0
+            }
+        })
+    }
+
+    /// Learn from the sample without correcting it.
+    pub fn update(&mut self, sample: IMUSample) {
+        self.recent.push_back(sample);
+        while self.recent.len() > self.detector.window.max(1) {
+            self.recent.pop_front();
+        }
+        self.stationary = self.detector.is_stationary(&self.recent);
+
+        if let Some(calibration) = &mut self.calibration {
+            if !self.stationary {
+                *calibration = Calibration::new(calibration.duration);
+                return;
+            }
+
+            calibration.sum = calibration.sum + sample.angular_velocity;
+            calibration.count += 1;
+            calibration.elapsed += self.sample_period;
+
+            if calibration.elapsed >= calibration.duration {
+                self.bias = GyroBias {
+                    bias: calibration.sum.scale(1.0 / calibration.count as f32),
+                    confidence: 1.0,
+                };
+                self.calibration = None;
+            }
+        } else if self.stationary {
+            let time_constant = self.time_constant.as_secs_f32();
+            let alpha = if time_constant > 0.0 {
+                (self.sample_period.as_secs_f32() / time_constant).min(1.0)
+            } else {
+                1.0
+            };
+
+            let bias = &mut self.bias;
+            bias.bias = bias.bias + (sample.angular_velocity - bias.bias).scale(alpha);
+            bias.confidence += (1.0 - bias.confidence) * alpha;
+        }
+    }
+
+    /// Learn from the sample, and subtract the bias from it.
+    pub fn correct(&mut self, sample: IMUSample) -> IMUSample {
+        self.update(sample);
+
+        IMUSample {
+            angular_velocity: sample.angular_velocity - self.bias.bias,
+            ..sample
+        }
+    }
+
+    /// Correct samples of a report, learning from the oldest first.
+    /// The corrected samples keep the order of the report.
+    pub fn correct_samples(&mut self, samples: [IMUSample; 3]) -> [IMUSample; 3] {
+        let mut corrected = samples;
+        IMUSample::fold_in_order(&samples, samples.len(), |index, sample| {
+            corrected[index - 1] = self.correct(*sample);
+            index - 1
+        });
+
+        corrected
+    }
+}
+
+/// Gyroscope bias estimators for each controller, keyed by serial number.
+#[derive(Debug, Clone, Default)]
+pub struct GyroBiasTracker {
+    template: GyroBiasEstimator,
+    estimators: HashMap<String, GyroBiasEstimator>,
+}
+
+impl GyroBiasTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tracker creating estimators like `template`.
+    pub fn with_template(template: GyroBiasEstimator) -> Self {
+        GyroBiasTracker {
+            template,
+            estimators: HashMap::new(),
+        }
+    }
+
+    pub fn estimator(&self, serial_number: &str) -> Option<&GyroBiasEstimator> {
+        self.estimators.get(serial_number)
+    }
+
+    /// Estimator of the controller. It is created if absent.
+    pub fn estimator_mut(&mut self, serial_number: &str) -> &mut GyroBiasEstimator {
+        let template = &self.template;
+        self.estimators
+            .entry(serial_number.to_string())
+            .or_insert_with(|| template.clone())
+    }
+
+    /// Current estimate of the controller.
+    pub fn bias(&self, serial_number: &str) -> Option<GyroBias> {
+        self.estimator(serial_number).map(GyroBiasEstimator::bias)
+    }
+
+    /// Estimates of all controllers.
+    pub fn biases(&self) -> Vec<(String, GyroBias)> {
+        self.estimators
+            .iter()
+            .map(|(serial_number, estimator)| (serial_number.clone(), estimator.bias()))
+            .collect()
+    }
+
+    /// See [`GyroBiasEstimator::calibrate_now`].
+    ///
+    /// [`GyroBiasEstimator::calibrate_now`]: struct.GyroBiasEstimator.html#method.calibrate_now
+    pub fn calibrate_now(&mut self, serial_number: &str, duration: Duration) {
+        self.estimator_mut(serial_number).calibrate_now(duration);
+    }
+
+    pub fn correct(&mut self, serial_number: &str, sample: IMUSample) -> IMUSample {
+        self.estimator_mut(serial_number).correct(sample)
+    }
+
+    /// See [`GyroBiasEstimator::correct_samples`].
+    ///
+    /// [`GyroBiasEstimator::correct_samples`]: struct.GyroBiasEstimator.html#method.correct_samples
+    pub fn correct_samples(
+        &mut self,
+        serial_number: &str,
+        samples: [IMUSample; 3],
+    ) -> [IMUSample; 3] {
+        self.estimator_mut(serial_number).correct_samples(samples)
+    }
+
+    /// Forget the controller.
+    pub fn remove(&mut self, serial_number: &str) -> Option<GyroBiasEstimator> {
+        self.estimators.remove(serial_number)
+    }
+}
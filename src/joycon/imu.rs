@@ -0,0 +1,440 @@
+//! IMU(6-Axis sensor) values in physical units.
+//!
+//! Raw [`AxisData`] is in counts of the sensor,
+//! and their scale depends on the sensitivity set by [`IMUConfig`] and the calibration stored in the device.
+//! [`IMUCalibrator`] converts them into accelerations in G and angular velocities in degrees per second.
+//!
+//! # Axes
+//! Each axis is right-handed, and positive rotations are counterclockwise seen from the positive side of the axis.
+//!
+//! * Pro Controller and Joy-Con (L) lying flat with buttons up:
+//!   X points toward the shoulder buttons, Y points to the left and Z points up.
+//! * Joy-Con (R): its axes haven't been verified on the device, so they aren't stated here.
+//!   [`IMUCalibrator::set_aligned`] negates X and Y,
+//!   which is how other drivers map Joy-Con (R) onto Joy-Con (L).
+//!
+//! At rest, the accelerometer measures 1G upward against gravity.
+//!
+//! Standard input reports carry 3 samples, [`IMUSample::PERIOD`] apart and the latest first.
+//! Filters in other modules take them oldest first through [`IMUSample::fold_in_order`].
+//!
+//! # Usage
+//! ```no_run
+//! use joycon_rs::prelude::{*, imu::*};
+//!
+//! fn print_motion(driver: SimpleJoyConDriver) -> JoyConResult<()> {
+//!     let config = driver.imu_config().unwrap_or_default();
+//!     let calibrator = IMUCalibrator::new(&driver.joycon(), config);
+//!     let standard_full_mode = StandardFullMode::new(driver)?;
+//!
+//!     loop {
+//!         let report = standard_full_mode.read_input_report()?;
+//!         let [latest, _, _] = report.extra.samples(&calibrator);
+//!         println!("{:?} G, {:?} dps", latest.acceleration, latest.angular_velocity);
+//!     }
+//! }
+//! ```
+//!
+//! [`AxisData`]: ../input_report_mode/standard_full_mode/struct.AxisData.html
+//! [`IMUConfig`]: ../joycon_features/imu_sensitivity/struct.IMUConfig.html
+//! [`IMUCalibrator`]: struct.IMUCalibrator.html
+//! [`IMUCalibrator::set_aligned`]: struct.IMUCalibrator.html#method.set_aligned
+//! [`IMUSample::PERIOD`]: struct.IMUSample.html#associatedconstant.PERIOD
+//! [`IMUSample::fold_in_order`]: struct.IMUSample.html#method.fold_in_order
+
+use super::device::calibration::imu::{IMUCalibration, IMUOffsets, XYZ};
+use super::input_report_mode::standard_full_mode::{AxisData, IMUData};
+use super::joycon_features::imu_sensitivity::{
+    AccelerometerSensitivity, GyroscopeSensitivity, IMUConfig,
+};
+use super::*;
+use std::time::Duration;
+
+/// Standard gravity in m/s².
+pub const STANDARD_GRAVITY: f32 = 9.80665;
+
+/// Vector of 3 axes.
+#[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vector3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vector3 {
+    pub fn new(x: f32, y: f32, z: f32) -> Self {
+        Vector3 { x, y, z }
+    }
+
+    pub fn magnitude(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn dot(&self, other: &Vector3) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: &Vector3) -> Vector3 {
+        Vector3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    pub fn scale(&self, factor: f32) -> Vector3 {
+        Vector3 {
+            x: self.x * factor,
+            y: self.y * factor,
+            z: self.z * factor,
+        }
+    }
+
+    /// Unit vector in the same direction. `None` for the zero vector.
+    pub fn normalized(&self) -> Option<Vector3> {
+        let magnitude = self.magnitude();
+        if magnitude > 0.0 {
+            Some(self.scale(1.0 / magnitude))
+        } else {
+            None
+        }
+    }
+}
+
+impl std::ops::Add for Vector3 {
+    type Output = Vector3;
+
+    fn add(self, rhs: Vector3) -> Vector3 {
+        Vector3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl std::ops::Sub for Vector3 {
+    type Output = Vector3;
+
+    fn sub(self, rhs: Vector3) -> Vector3 {
+        Vector3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl std::ops::Neg for Vector3 {
+    type Output = Vector3;
+
+    fn neg(self) -> Vector3 {
+        Vector3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+/// A sample of the IMU in physical units.
+#[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct IMUSample {
+    /// Acceleration in G.
+    pub acceleration: Vector3,
+    /// Angular velocity in degrees per second.
+    pub angular_velocity: Vector3,
+}
+
+impl IMUSample {
+    /// Period between samples of a report.
+    pub const PERIOD: Duration = Duration::from_millis(5);
+
+    /// Fold samples of a report from the oldest, while reports have the latest first.
+    ///
+    /// # Example
+    /// ```
+    /// use joycon_rs::prelude::imu::*;
+    ///
+    /// let sample = |x| IMUSample {
+    ///     acceleration: Vector3::new(x, 0.0, 0.0),
+    ///     angular_velocity: Vector3::default(),
+    /// };
+    /// let report = [sample(3.0), sample(2.0), sample(1.0)];
+    ///
+    /// let order = IMUSample::fold_in_order(&report, Vec::new(), |mut order, sample| {
+    ///     order.push(sample.acceleration.x);
+    ///     order
+    /// });
+    /// assert_eq!(order, vec![1.0, 2.0, 3.0]);
+    /// ```
+    pub fn fold_in_order<T, F>(samples: &[IMUSample], init: T, f: F) -> T
+    where
+        F: FnMut(T, &IMUSample) -> T,
+    {
+        samples.iter().rev().fold(init, f)
+    }
+
+    /// Acceleration in m/s².
+    pub fn acceleration_mps2(&self) -> Vector3 {
+        self.acceleration.scale(STANDARD_GRAVITY)
+    }
+
+    /// Angular velocity in radians per second.
+    pub fn angular_velocity_rads(&self) -> Vector3 {
+        self.angular_velocity.scale(std::f32::consts::PI / 180.0)
+    }
+}
+
+/// Scale of an axis: `value = (raw - origin) * scale`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AxisScale {
+    origin: f32,
+    scale: f32,
+}
+
+impl AxisScale {
+    fn new(origin: i16, coeff: i16, full_scale_at_coeff: f32) -> Self {
+        let span = coeff as f32 - origin as f32;
+        let scale = if span == 0.0 {
+            0.0
+        } else {
+            full_scale_at_coeff / span
+        };
+
+        AxisScale {
+            origin: origin as f32,
+            scale,
+        }
+    }
+
+    fn apply(&self, raw: i16) -> f32 {
+        (raw as f32 - self.origin) * self.scale
+    }
+}
+
+/// Converts raw IMU data into physical units.
+///
+/// It uses the user calibration of the device if available, otherwise the factory calibration.
+/// Without both, the nominal coefficients are used.
+///
+/// The horizontal offsets of the device are what the accelerometer reads lying level,
+/// gravity included, rather than a bias of the sensor.
+/// So they are not subtracted from samples, with or without calibration,
+/// and are provided by [`horizontal`](#method.horizontal) as the reference of being level.
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{*, imu::*, input_report_mode::standard_full_mode::AxisData, joycon_features::IMUConfig};
+///
+/// let calibrator = IMUCalibrator::nominal(JoyConDeviceType::ProCon, IMUConfig::default());
+///
+/// let data = AxisData {
+///     accel_x: 0,
+///     accel_y: 0,
+///     accel_z: 4096,
+///     gyro_1: 0,
+///     gyro_2: 0,
+///     gyro_3: 13371,
+/// };
+/// let sample = calibrator.convert(&data);
+///
+/// assert_eq!(sample.acceleration, Vector3::new(0.0, 0.0, 1.0));
+/// assert!((sample.angular_velocity.z - 936.0).abs() < 1e-3);
+///
+/// // Horizontal offsets are not subtracted from samples
+/// use joycon_rs::joycon::device::calibration::imu::{IMUCalibration, IMUOffsets};
+///
+/// let calibrator = IMUCalibrator::with_calibration(
+///     JoyConDeviceType::ProCon,
+///     IMUConfig::default(),
+///     &IMUCalibration::Unavailable,
+///     &IMUOffsets { x: 0, y: 0, z: 4096 },
+/// );
+/// assert_eq!(calibrator.horizontal(), Vector3::new(0.0, 0.0, 1.0));
+/// assert_eq!(calibrator.convert(&data).acceleration, Vector3::new(0.0, 0.0, 1.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct IMUCalibrator {
+    device_type: JoyConDeviceType,
+    config: IMUConfig,
+    accel: [AxisScale; 3],
+    gyro: [AxisScale; 3],
+    horizontal: Vector3,
+    aligned: bool,
+}
+
+impl IMUCalibrator {
+    /// Accelerometer coefficient when the device has no calibration. It's 4G at ±8G.
+    pub const NOMINAL_ACCEL_COEFF: i16 = 16384;
+    /// Gyroscope coefficient when the device has no calibration. It's 936dps at ±2000dps.
+    pub const NOMINAL_GYRO_COEFF: i16 = 13371;
+
+    /// Calibrator with the calibration of the device, for data measured with `config`.
+    pub fn new(device: &JoyConDevice, config: IMUConfig) -> Self {
+        let calibration = match device.imu_user_calibration() {
+            IMUCalibration::Available { .. } => device.imu_user_calibration(),
+            IMUCalibration::Unavailable => device.imu_factory_calibration(),
+        };
+
+        Self::with_calibration(
+            device.device_type(),
+            config,
+            calibration,
+            device.imu_offsets(),
+        )
+    }
+
+    /// Calibrator with nominal coefficients, for devices without calibration.
+    pub fn nominal(device_type: JoyConDeviceType, config: IMUConfig) -> Self {
+        Self::with_calibration(
+            device_type,
+            config,
+            &IMUCalibration::Unavailable,
+            &IMUOffsets { x: 0, y: 0, z: 0 },
+        )
+    }
+
+    /// Calibrator with the calibration, for data measured with `config`.
+    ///
+    /// `offsets` are the horizontal offsets of the device. See [`horizontal`](#method.horizontal).
+    pub fn with_calibration(
+        device_type: JoyConDeviceType,
+        config: IMUConfig,
+        calibration: &IMUCalibration,
+        offsets: &IMUOffsets,
+    ) -> Self {
+        let nominal = |value| XYZ {
+            x: value,
+            y: value,
+            z: value,
+        };
+        let (acc_origin, acc_coeff, gyro_origin, gyro_coeff) = match calibration {
+            IMUCalibration::Available {
+                acc_origin_position,
+                acc_sensitivity_special_coeff,
+                gyro_origin_position,
+                gyro_sensitivity_special_coeff,
+            } => (
+                acc_origin_position.clone(),
+                acc_sensitivity_special_coeff.clone(),
+                gyro_origin_position.clone(),
+                gyro_sensitivity_special_coeff.clone(),
+            ),
+            IMUCalibration::Unavailable => (
+                nominal(0),
+                nominal(Self::NOMINAL_ACCEL_COEFF),
+                nominal(0),
+                nominal(Self::NOMINAL_GYRO_COEFF),
+            ),
+        };
+
+        // Coefficients are measured at ±8G and ±2000dps.
+        let accel_full_scale = 4.0 * accel_range(config.accelerometer_sensitivity) / 8.0;
+        let gyro_full_scale = 936.0 * gyro_range(config.gyroscope_sensitivity) / 2000.0;
+
+        let scales = |origin: XYZ<i16>, coeff: XYZ<i16>, full_scale| {
+            [
+                AxisScale::new(origin.x, coeff.x, full_scale),
+                AxisScale::new(origin.y, coeff.y, full_scale),
+                AxisScale::new(origin.z, coeff.z, full_scale),
+            ]
+        };
+
+        IMUCalibrator {
+            device_type,
+            config,
+            accel: scales(acc_origin, acc_coeff, accel_full_scale),
+            gyro: scales(gyro_origin, gyro_coeff, gyro_full_scale),
+            // Offsets are stored in counts at ±8G
+            horizontal: Vector3::new(offsets.x as f32, offsets.y as f32, offsets.z as f32)
+                .scale(4.0 / Self::NOMINAL_ACCEL_COEFF as f32),
+            aligned: false,
+        }
+    }
+
+    pub fn device_type(&self) -> JoyConDeviceType {
+        self.device_type.clone()
+    }
+
+    pub fn config(&self) -> IMUConfig {
+        self.config
+    }
+
+    /// Whether axes of Joy-Con (R) are aligned to the others.
+    pub fn aligned(&self) -> bool {
+        self.aligned
+    }
+
+    /// Negate X and Y of Joy-Con (R). See the [module documentation].
+    ///
+    /// [module documentation]: index.html#axes
+    pub fn set_aligned(&mut self, aligned: bool) {
+        self.aligned = aligned;
+    }
+
+    /// Acceleration in G the device reads lying level, from its horizontal offsets.
+    /// Tilt from level can be told by comparing samples at rest with it.
+    pub fn horizontal(&self) -> Vector3 {
+        self.align(self.horizontal)
+    }
+
+    /// Convert a frame of IMU data.
+    pub fn convert(&self, data: &AxisData) -> IMUSample {
+        let [ax, ay, az] = self.accel;
+        let [gx, gy, gz] = self.gyro;
+
+        let acceleration = Vector3::new(
+            ax.apply(data.accel_x),
+            ay.apply(data.accel_y),
+            az.apply(data.accel_z),
+        );
+        let angular_velocity = Vector3::new(
+            gx.apply(data.gyro_1),
+            gy.apply(data.gyro_2),
+            gz.apply(data.gyro_3),
+        );
+
+        IMUSample {
+            acceleration: self.align(acceleration),
+            angular_velocity: self.align(angular_velocity),
+        }
+    }
+
+    fn align(&self, vector: Vector3) -> Vector3 {
+        if self.aligned && self.device_type == JoyConDeviceType::JoyConR {
+            Vector3::new(-vector.x, -vector.y, vector.z)
+        } else {
+            vector
+        }
+    }
+
+    /// Convert 3 frames of IMU data. The latest comes first.
+    pub fn samples(&self, data: &IMUData) -> [IMUSample; 3] {
+        let [latest, older, oldest] = &data.data;
+        [
+            self.convert(latest),
+            self.convert(older),
+            self.convert(oldest),
+        ]
+    }
+}
+
+impl IMUData {
+    /// Samples in physical units. The latest comes first. See [`IMUCalibrator`].
+    ///
+    /// [`IMUCalibrator`]: ../imu/struct.IMUCalibrator.html
+    pub fn samples(&self, calibrator: &IMUCalibrator) -> [IMUSample; 3] {
+        calibrator.samples(self)
+    }
+}
+
+/// Range in G.
+fn accel_range(sensitivity: AccelerometerSensitivity) -> f32 {
+    match sensitivity {
+        AccelerometerSensitivity::PM2G => 2.0,
+        AccelerometerSensitivity::PM4G => 4.0,
+        AccelerometerSensitivity::PM8G => 8.0,
+        AccelerometerSensitivity::PM16G => 16.0,
+    }
+}
+
+/// Range in degrees per second.
+fn gyro_range(sensitivity: GyroscopeSensitivity) -> f32 {
+    match sensitivity {
+        GyroscopeSensitivity::PM250dps => 250.0,
+        GyroscopeSensitivity::PM500dps => 500.0,
+        GyroscopeSensitivity::PM1000dps => 1000.0,
+        GyroscopeSensitivity::PM2000dps => 2000.0,
+    }
+}
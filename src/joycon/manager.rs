@@ -17,13 +17,20 @@ pub struct JoyConSerialNumber(pub String);
 ///
 /// You can get instance at [`JoyConManager::get_instance()`].
 ///
+/// Optionally, the manager assigns player slots to Joy-Cons as Nintendo Switch does.
+/// See [`JoyConManager::set_player_slots_enabled()`].
+///
 /// [`JoyConManager::with_duration()`]: #method.with_duration
 /// [`JoyConManager::get_instance()`]: #method.get_instance
+/// [`JoyConManager::set_player_slots_enabled()`]: #method.set_player_slots_enabled
 pub struct JoyConManager {
     devices: HashMap<JoyConSerialNumber, Arc<Mutex<JoyConDevice>>>,
     hid_api: Option<HidApi>,
     scanner: Option<JoinHandle<()>>,
     scan_interval: Duration,
+    retry_policy: RetryPolicy,
+    /// `None` if player slots are disabled.
+    player_slots: Option<HashMap<JoyConSerialNumber, usize>>,
     new_devices: crossbeam_channel::Receiver<Arc<Mutex<JoyConDevice>>>,
 }
 
@@ -40,7 +47,7 @@ impl JoyConManager {
                 SINGLETON = Some(instance);
             });
 
-            match SINGLETON.clone() {
+            match (*std::ptr::addr_of!(SINGLETON)).clone() {
                 Some(manager) => manager,
                 None => unreachable!(),
             }
@@ -62,6 +69,8 @@ impl JoyConManager {
                 hid_api: None,
                 scanner: None,
                 scan_interval: interval,
+                retry_policy: RetryPolicy::default(),
+                player_slots: None,
                 new_devices: rx,
             };
 
@@ -121,6 +130,11 @@ impl JoyConManager {
         self.scan_interval = interval;
     }
 
+    /// Set timeout and retry policy for loading calibrations of newly detected Joy-Cons.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
     /// Scan the JoyCon connected to your computer.
     /// This returns new Joy-Cons.
     pub fn scan(&mut self) -> JoyConResult<Vec<Arc<Mutex<JoyConDevice>>>> {
@@ -137,6 +151,7 @@ impl JoyConManager {
             }
         };
 
+        let retry_policy = self.retry_policy;
         let previous_device_serials = self.devices.keys().cloned().collect::<HashSet<_>>();
 
         let detected_device_serials = hid_api
@@ -158,7 +173,7 @@ impl JoyConManager {
                     .serial_number()
                     .map(|s| s.to_string())
                     .map(JoyConSerialNumber)?;
-                let device = JoyConDevice::new(di, hid_api).ok()?;
+                let device = JoyConDevice::with_retry_policy(di, hid_api, &retry_policy).ok()?;
                 Some((serial_number, device))
             })
             .map(|(serial, device)| (serial, Arc::new(Mutex::new(device))))
@@ -215,11 +230,18 @@ impl JoyConManager {
                         }
                     };
 
-                    *device = new_device;
+                    device.reset_device_from(new_device);
+                    // Rumble may be left on from before the disconnection
+                    let _ = device.stop_rumble();
                 } else {
                     unreachable!()
                 }
             });
+
+            // Show the slots again, since player lights are reset by the reconnection
+            reconnected_keys
+                .iter()
+                .for_each(|key| self.show_player_slot(key));
         }
 
         let mut new_devices = Vec::new();
@@ -228,10 +250,21 @@ impl JoyConManager {
             let connected_keys = detected_device_serials.difference(&previous_device_serials);
             connected_keys.for_each(|key| {
                 if let Some(device) = detected_devices.remove(key) {
+                    // Rumble may be left on by a previous process
+                    let _ = match device.lock() {
+                        Ok(mut d) => d.stop_rumble(),
+                        Err(e) => e.into_inner().stop_rumble(),
+                    };
+
                     let device_cloned = Arc::clone(&device);
                     new_devices.push(device_cloned);
 
                     self.devices.insert(key.clone(), device);
+
+                    if self.player_slots.is_some() {
+                        self.assign_player_slot(key);
+                        self.show_player_slot(key);
+                    }
                 }
             });
         }
@@ -239,6 +272,195 @@ impl JoyConManager {
         Ok(new_devices)
     }
 
+    /// Max number of player slots.
+    pub const MAX_PLAYER_SLOTS: usize = 8;
+
+    /// Enable or disable assignment of player slots.
+    ///
+    /// While enabled, each newly connected Joy-Con gets the lowest free slot (0 - 7),
+    /// and the slot is shown on its player lights.
+    /// Joy-Cons keep their slots across reconnections.
+    /// Enabling assigns slots to managed Joy-Cons in order of serial number.
+    /// Disabling forgets all slots.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use joycon_rs::prelude::*;
+    ///
+    /// let manager = JoyConManager::get_instance();
+    /// let mut manager = manager.lock().unwrap();
+    /// manager.set_player_slots_enabled(true);
+    ///
+    /// for device in manager.managed_devices() {
+    ///     let serial_number = device.lock().unwrap().serial_number().to_string();
+    ///     dbg!(manager.player_slot(&serial_number));
+    /// }
+    ///
+    /// // Player 1 and player 2 trade places
+    /// manager.swap_player_slots(0, 1);
+    /// ```
+    pub fn set_player_slots_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.player_slots = None;
+            return;
+        }
+        if self.player_slots.is_some() {
+            return;
+        }
+
+        self.player_slots = Some(HashMap::new());
+
+        let mut keys = self.devices.keys().cloned().collect::<Vec<_>>();
+        keys.sort_by(|a, b| a.0.cmp(&b.0));
+        keys.iter().for_each(|key| {
+            self.assign_player_slot(key);
+            self.show_player_slot(key);
+        });
+    }
+
+    pub fn player_slots_enabled(&self) -> bool {
+        self.player_slots.is_some()
+    }
+
+    /// Player slot of the Joy-Con.
+    pub fn player_slot(&self, serial_number: &str) -> Option<usize> {
+        let key = JoyConSerialNumber(serial_number.to_string());
+        self.player_slots.as_ref()?.get(&key).copied()
+    }
+
+    /// Serial numbers of Joy-Cons and their slots, in order of slot.
+    pub fn player_slots(&self) -> Vec<(String, usize)> {
+        let mut slots = self
+            .player_slots
+            .iter()
+            .flatten()
+            .map(|(key, &slot)| (key.0.clone(), slot))
+            .collect::<Vec<_>>();
+        slots.sort_by_key(|&(_, slot)| slot);
+
+        slots
+    }
+
+    /// Swap Joy-Cons of two slots. If one of them is free, the Joy-Con moves to it.
+    pub fn swap_player_slots(&mut self, a: usize, b: usize) {
+        let slots = match &mut self.player_slots {
+            Some(slots) if a < Self::MAX_PLAYER_SLOTS && b < Self::MAX_PLAYER_SLOTS => slots,
+            _ => return,
+        };
+
+        let mut moved = Vec::new();
+        slots.iter_mut().for_each(|(key, slot)| {
+            if *slot == a {
+                *slot = b;
+                moved.push(key.clone());
+            } else if *slot == b {
+                *slot = a;
+                moved.push(key.clone());
+            }
+        });
+
+        moved.iter().for_each(|key| self.show_player_slot(key));
+    }
+
+    /// Assign slots from 0 in order of the serial numbers.
+    /// Joy-Cons not listed follow them, keeping their order.
+    /// Joy-Cons past `MAX_PLAYER_SLOTS` lose their slots, and their player lights are turned off.
+    pub fn reorder_player_slots(&mut self, serial_numbers: &[&str]) {
+        let devices = &self.devices;
+        let slots = match &mut self.player_slots {
+            Some(slots) => slots,
+            None => return,
+        };
+
+        let listed = serial_numbers
+            .iter()
+            .map(|s| JoyConSerialNumber(s.to_string()))
+            .filter(|key| devices.contains_key(key));
+        let mut others = slots
+            .iter()
+            .filter(|(key, _)| !serial_numbers.contains(&key.0.as_str()))
+            .map(|(key, &slot)| (key.clone(), slot))
+            .collect::<Vec<_>>();
+        others.sort_by_key(|&(_, slot)| slot);
+
+        let mut order = Vec::new();
+        listed
+            .chain(others.into_iter().map(|(key, _)| key))
+            .for_each(|key| {
+                if !order.contains(&key) {
+                    order.push(key);
+                }
+            });
+        let unslotted = order.split_off(order.len().min(Self::MAX_PLAYER_SLOTS));
+
+        *slots = order
+            .iter()
+            .enumerate()
+            .map(|(slot, key)| (key.clone(), slot))
+            .collect();
+
+        order.iter().for_each(|key| self.show_player_slot(key));
+        // Turn off the stale slots of Joy-Cons left without one
+        unslotted.iter().for_each(|key| {
+            if let Some(device) = self.devices.get(key) {
+                let _ = Self::send_player_lights(device, 0);
+            }
+        });
+    }
+
+    /// Free the slot of the Joy-Con, and turn its player lights off. This returns the slot.
+    pub fn release_player_slot(&mut self, serial_number: &str) -> Option<usize> {
+        let key = JoyConSerialNumber(serial_number.to_string());
+        let slot = self.player_slots.as_mut()?.remove(&key)?;
+
+        if let Some(device) = self.devices.get(&key) {
+            let _ = Self::send_player_lights(device, 0);
+        }
+
+        Some(slot)
+    }
+
+    /// Assign the lowest free slot unless the Joy-Con has one.
+    fn assign_player_slot(&mut self, key: &JoyConSerialNumber) -> Option<usize> {
+        let slots = self.player_slots.as_mut()?;
+        if let Some(&slot) = slots.get(key) {
+            return Some(slot);
+        }
+
+        let slot = (0..Self::MAX_PLAYER_SLOTS).find(|slot| !slots.values().any(|s| s == slot))?;
+        slots.insert(key.clone(), slot);
+
+        Some(slot)
+    }
+
+    /// Show the slot on player lights without waiting for the reply.
+    fn show_player_slot(&self, key: &JoyConSerialNumber) {
+        let (slot, device) = match (self.player_slots.as_ref(), self.devices.get(key)) {
+            (Some(slots), Some(device)) => match slots.get(key) {
+                Some(&slot) => (slot, device),
+                None => return,
+            },
+            _ => return,
+        };
+
+        let arg = lights::PLAYER_LIGHTS[slot]
+            .iter()
+            .fold(0, |arg, &light_up| arg | light_up as u8);
+
+        // Disconnected Joy-Cons show the slot on reconnection
+        let _ = Self::send_player_lights(device, arg);
+    }
+
+    /// Set player lights without waiting for the reply.
+    fn send_player_lights(device: &Mutex<JoyConDevice>, arg: u8) -> JoyConResult<usize> {
+        JoyConDevice::send_paced(
+            device,
+            Command::RumbleAndSubCommand as u8,
+            SubCommand::SetPlayerLights as u8,
+            &[arg],
+        )
+    }
+
     /// Collection of managed JoyCons.
     /// It may contains disconnected ones.
     pub fn managed_devices(&self) -> Vec<Arc<Mutex<JoyConDevice>>> {
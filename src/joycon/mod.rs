@@ -2,9 +2,10 @@ use crate::prelude::*;
 
 pub use device::{JoyConDevice, JoyConDeviceType};
 pub use driver::{
-    device_info,
-    input_report_mode::{self, InputReportMode, SimpleHIDMode, StandardFullMode},
-    joycon_features, lights, Command, GlobalPacketNumber, JoyConDriver, Rotation, Rumble,
+    audio_rumble, device_info,
+    input_report_mode::{self, InputReportMode, SimpleHIDMode, StandardFullMode, SubCommandMode},
+    joycon_features, lights, retry_policy, rumble_limiter, rumble_sequencer, Command,
+    GlobalPacketNumber, JoyConDriver, RetryPolicy, Rotation, Rumble, RumbleBand, RumbleLimiter,
     SimpleJoyConDriver, SubCommand, SubCommandReply,
 };
 pub use manager::{JoyConManager, JOYCON_RECEIVER};
@@ -33,11 +34,28 @@ pub enum Buttons {
     Capture,
     L,
     ZL,
+    /// SL of either side. Reports tell it as [`LeftSL`] or [`RightSL`].
+    ///
+    /// [`LeftSL`]: #variant.LeftSL
+    /// [`RightSL`]: #variant.RightSL
     SL,
+    /// SR of either side. Reports tell it as [`LeftSR`] or [`RightSR`].
+    ///
+    /// [`LeftSR`]: #variant.LeftSR
+    /// [`RightSR`]: #variant.RightSR
     SR,
     ChargingGrip,
+    /// SL of Joy-Con (L)
+    LeftSL,
+    /// SR of Joy-Con (L)
+    LeftSR,
+    /// SL of Joy-Con (R)
+    RightSL,
+    /// SR of Joy-Con (R)
+    RightSR,
 }
 
+#[allow(dead_code)]
 struct DebugHidDevice<'a>(&'a HidDevice);
 
 impl<'a> Debug for DebugHidDevice<'a> {
@@ -50,6 +68,16 @@ impl<'a> Debug for DebugHidDevice<'a> {
     }
 }
 
+pub mod aim;
+pub mod button_events;
+pub mod combo;
 pub mod device;
 mod driver;
+pub mod gesture;
+pub mod gyro_bias;
+pub mod imu;
 mod manager;
+pub mod motion_template;
+pub mod orientation;
+pub mod pointer;
+pub mod stick;
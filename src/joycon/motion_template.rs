@@ -0,0 +1,457 @@
+//! Recognize recorded motions.
+//!
+//! Record example motions with [`MotionRecorder`], and [`TemplateMatcher`] finds them in IMU samples
+//! with dynamic time warping, so motions performed slower or faster still match.
+//! With `use_serde` feature, [`MotionTemplate`]s can be saved and loaded in any format of serde.
+//!
+//! # Usage
+//! ```no_run
+//! use joycon_rs::prelude::{*, imu::*, motion_template::*};
+//!
+//! // Record a move while A is pushed
+//! fn record(standard_full_mode: &StandardFullMode<SimpleJoyConDriver>, calibrator: &IMUCalibrator) -> JoyConResult<MotionTemplate> {
+//!     let mut recorder = MotionRecorder::new();
+//!
+//!     loop {
+//!         let report = standard_full_mode.read_input_report()?;
+//!         if report.common.pushed_buttons.contains(Buttons::A) {
+//!             recorder.push_samples(&report.extra.samples(calibrator));
+//!         } else if !recorder.samples().is_empty() {
+//!             return Ok(recorder.finish("spin"));
+//!         }
+//!     }
+//! }
+//!
+//! fn recognize(standard_full_mode: &StandardFullMode<SimpleJoyConDriver>, calibrator: &IMUCalibrator) -> JoyConResult<()> {
+//!     let mut matcher = TemplateMatcher::new();
+//!     matcher.add_template(record(standard_full_mode, calibrator)?);
+//!
+//!     loop {
+//!         let report = standard_full_mode.read_input_report()?;
+//!         if let Some(found) = matcher.update_samples(&report.extra.samples(calibrator)) {
+//!             println!("{} ({:.2})", found.name, found.confidence);
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`TemplateMatcher`]: struct.TemplateMatcher.html
+//! [`MotionRecorder`]: struct.MotionRecorder.html
+//! [`MotionTemplate`]: struct.MotionTemplate.html
+
+use super::imu::IMUSample;
+use super::*;
+
+/// A recorded motion.
+#[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotionTemplate {
+    pub name: String,
+    /// Samples in order of time.
+    pub samples: Vec<IMUSample>,
+}
+
+impl MotionTemplate {
+    pub fn new(name: impl Into<String>, samples: Vec<IMUSample>) -> Self {
+        MotionTemplate {
+            name: name.into(),
+            samples,
+        }
+    }
+}
+
+/// Records samples into a [`MotionTemplate`].
+///
+/// [`MotionTemplate`]: struct.MotionTemplate.html
+#[derive(Debug, Clone, Default)]
+pub struct MotionRecorder {
+    samples: Vec<IMUSample>,
+}
+
+impl MotionRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, sample: IMUSample) {
+        self.samples.push(sample);
+    }
+
+    /// Push samples of a report, the oldest first.
+    pub fn push_samples(&mut self, samples: &[IMUSample]) {
+        self.samples.extend(samples.iter().rev());
+    }
+
+    /// Samples recorded so far.
+    pub fn samples(&self) -> &[IMUSample] {
+        &self.samples
+    }
+
+    /// Make a template of the samples, and start over.
+    pub fn finish(&mut self, name: impl Into<String>) -> MotionTemplate {
+        MotionTemplate::new(name, std::mem::take(&mut self.samples))
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
+
+/// A template found in samples.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateMatch {
+    pub name: String,
+    /// From 0.0 to 1.0. Higher is closer.
+    pub confidence: f32,
+    /// Mean distance between matched samples.
+    pub distance: f32,
+}
+
+/// Finds [`MotionTemplate`]s in IMU samples with dynamic time warping.
+///
+/// The distance between samples is the euclidean distance of acceleration in G times `accel_weight`
+/// and angular velocity in turns per second times `gyro_weight`.
+/// The confidence is `exp(-distance / tolerance)`.
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{imu::*, motion_template::*};
+///
+/// let motion = |length: usize, speed: f32| -> Vec<IMUSample> {
+///     (0..length)
+///         .map(|i| {
+///             let phase = i as f32 / length as f32 * std::f32::consts::PI;
+///             IMUSample {
+///                 acceleration: Vector3::new(0.0, 0.0, 1.0),
+///                 angular_velocity: Vector3::new(0.0, 0.0, speed * phase.sin()),
+///             }
+///         })
+///         .collect()
+/// };
+///
+/// let mut matcher = TemplateMatcher::new();
+/// matcher.add_template(MotionTemplate::new("turn", motion(60, 360.0)));
+///
+/// // Performed slower
+/// let found = matcher.match_motion(&motion(90, 360.0)).unwrap();
+/// assert_eq!(found.name, "turn");
+/// assert!(found.confidence > 0.9);
+///
+/// // Turned the other way
+/// assert_eq!(matcher.match_motion(&motion(60, -360.0)), None);
+/// ```
+///
+/// [`MotionTemplate`]: struct.MotionTemplate.html
+#[derive(Debug, Clone)]
+pub struct TemplateMatcher {
+    pub accel_weight: f32,
+    pub gyro_weight: f32,
+    /// Distance where the confidence is `1 / e`.
+    pub tolerance: f32,
+    /// Matches less confident than this are ignored.
+    pub min_confidence: f32,
+    /// Samples without closer matches before `update` reports a match.
+    pub settle_samples: usize,
+    templates: Vec<MotionTemplate>,
+    /// Warping of each template against the samples passed to `update`.
+    warps: Vec<Warp>,
+    /// The closest match so far, and samples since it.
+    candidate: Option<(TemplateMatch, usize)>,
+}
+
+impl Default for TemplateMatcher {
+    fn default() -> Self {
+        TemplateMatcher::new()
+    }
+}
+
+impl TemplateMatcher {
+    /// Angular velocity of a turn per second, in dps.
+    const TURN: f32 = 360.0;
+    /// Room for motions twice slower than templates in `update`.
+    const WINDOW: usize = 2;
+
+    pub fn new() -> Self {
+        TemplateMatcher {
+            accel_weight: 1.0,
+            gyro_weight: 1.0,
+            tolerance: 0.25,
+            min_confidence: 0.6,
+            settle_samples: 6,
+            templates: Vec::new(),
+            warps: Vec::new(),
+            candidate: None,
+        }
+    }
+
+    pub fn templates(&self) -> &[MotionTemplate] {
+        &self.templates
+    }
+
+    /// Add a template. Templates without samples are ignored.
+    pub fn add_template(&mut self, template: MotionTemplate) {
+        if !template.samples.is_empty() {
+            self.warps
+                .push(Warp::new(template.samples.len(), Self::WINDOW));
+            self.templates.push(template);
+        }
+    }
+
+    /// Remove templates named `name`.
+    pub fn remove_template(&mut self, name: &str) {
+        let mut removed = self.templates.iter().map(|template| template.name == name);
+        self.warps.retain(|_| !removed.next().unwrap_or(false));
+        self.templates.retain(|template| template.name != name);
+    }
+
+    /// The template closest to the whole `samples`.
+    pub fn match_motion(&self, samples: &[IMUSample]) -> Option<TemplateMatch> {
+        let cost = self.cost();
+        let distances = self.templates.iter().map(|template| {
+            let mut warp = Warp::new(template.samples.len(), usize::MAX);
+            samples
+                .iter()
+                .enumerate()
+                .map(|(i, sample)| warp.push(&template.samples, sample, i == 0, cost))
+                .last()
+                .flatten()
+        });
+
+        self.best_match(distances)
+    }
+
+    /// Update with a sample, and look for templates in recent samples.
+    ///
+    /// A match is reported `settle_samples` samples after the closest point.
+    /// Samples are forgotten after a match, so a motion matches once.
+    /// Each sample costs time proportional to the total length of the templates.
+    ///
+    /// # Example
+    /// ```
+    /// use joycon_rs::prelude::{imu::*, motion_template::*};
+    ///
+    /// let sample = |speed: f32| IMUSample {
+    ///     acceleration: Vector3::new(0.0, 0.0, 1.0),
+    ///     angular_velocity: Vector3::new(0.0, 0.0, speed),
+    /// };
+    /// let turn = |length: usize| (0..length).map(move |i| {
+    ///     let phase = i as f32 / length as f32 * std::f32::consts::PI;
+    ///     sample(360.0 * phase.sin())
+    /// });
+    ///
+    /// let mut matcher = TemplateMatcher::new();
+    /// matcher.add_template(MotionTemplate::new("turn", turn(60).collect()));
+    ///
+    /// // Still, turning a bit faster, and still again
+    /// let still = std::iter::repeat(sample(0.0)).take(40);
+    /// let found = still.clone()
+    ///     .chain(turn(50))
+    ///     .chain(still)
+    ///     .filter_map(|sample| matcher.update(sample))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(found.len(), 1);
+    /// assert_eq!(found[0].name, "turn");
+    /// ```
+    pub fn update(&mut self, sample: IMUSample) -> Option<TemplateMatch> {
+        let cost = self.cost();
+        let distances = self
+            .templates
+            .iter()
+            .zip(&mut self.warps)
+            .map(|(template, warp)| warp.push(&template.samples, &sample, true, cost))
+            .collect::<Vec<_>>();
+
+        let found = self.best_match(distances.into_iter());
+        self.settle(found)
+    }
+
+    /// Update with samples of a report, and return the first match among them.
+    pub fn update_samples(&mut self, samples: &[IMUSample]) -> Option<TemplateMatch> {
+        IMUSample::fold_in_order(samples, None, |found, sample| {
+            let next = self.update(*sample);
+            found.or(next)
+        })
+    }
+
+    /// Forget samples passed to `update`.
+    pub fn clear_history(&mut self) {
+        self.warps.iter_mut().for_each(Warp::clear);
+        self.candidate = None;
+    }
+
+    fn settle(&mut self, found: Option<TemplateMatch>) -> Option<TemplateMatch> {
+        // Wait for the end of the motion, where the match is the closest
+        match (found, &mut self.candidate) {
+            (Some(found), Some((candidate, _))) if found.confidence > candidate.confidence => {
+                self.candidate = Some((found, 0));
+            }
+            (Some(found), None) => self.candidate = Some((found, 0)),
+            (_, Some((_, settled))) => *settled += 1,
+            (None, None) => {}
+        }
+
+        match self.candidate.take() {
+            Some((candidate, settled)) if settled >= self.settle_samples => {
+                self.clear_history();
+                Some(candidate)
+            }
+            candidate => {
+                self.candidate = candidate;
+                None
+            }
+        }
+    }
+
+    /// The closest of templates, given the distance to each of them.
+    fn best_match<I>(&self, distances: I) -> Option<TemplateMatch>
+    where
+        I: Iterator<Item = Option<f32>>,
+    {
+        self.templates
+            .iter()
+            .zip(distances)
+            .filter_map(|(template, distance)| {
+                let distance = distance?;
+                let confidence = if self.tolerance > 0.0 {
+                    (-distance / self.tolerance).exp()
+                } else if distance == 0.0 {
+                    1.0
+                } else {
+                    0.0
+                };
+
+                Some(TemplateMatch {
+                    name: template.name.clone(),
+                    confidence,
+                    distance,
+                })
+            })
+            .filter(|found| found.confidence >= self.min_confidence)
+            .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+    }
+
+    fn cost(&self) -> impl Fn(&IMUSample, &IMUSample) -> f32 + Copy {
+        let (accel_weight, gyro_weight) = (self.accel_weight, self.gyro_weight / Self::TURN);
+
+        move |a, b| {
+            let accel = (a.acceleration - b.acceleration).scale(accel_weight);
+            let gyro = (a.angular_velocity - b.angular_velocity).scale(gyro_weight);
+
+            (accel.dot(&accel) + gyro.dot(&gyro)).sqrt()
+        }
+    }
+}
+
+/// Warping path ending at a sample of a template.
+#[derive(Debug, Clone, Copy)]
+struct Step {
+    /// Total cost along the path.
+    cost: f32,
+    /// Cells along the path.
+    length: u32,
+    /// The sample where the path started.
+    start: usize,
+}
+
+impl Step {
+    const UNREACHABLE: Step = Step {
+        cost: f32::INFINITY,
+        length: 0,
+        start: 0,
+    };
+
+    /// Mean cost along the path, after a cell costing `cost` is added.
+    fn mean_with(&self, cost: f32) -> f32 {
+        (self.cost + cost) / (self.length + 1) as f32
+    }
+}
+
+/// Dynamic time warping of a template against samples, a sample at a time.
+#[derive(Debug, Clone)]
+struct Warp {
+    /// Best paths ending at each sample of the template and the latest sample.
+    /// The first one is the start before the template.
+    column: Vec<Step>,
+    /// Samples pushed so far.
+    samples: usize,
+    /// Max samples along a path.
+    window: usize,
+}
+
+impl Warp {
+    /// Paths over more than `window` times the samples of the template are ignored.
+    fn new(template_len: usize, window: usize) -> Self {
+        Warp {
+            column: vec![Step::UNREACHABLE; template_len + 1],
+            samples: 0,
+            window: template_len.saturating_mul(window),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.column
+            .iter_mut()
+            .for_each(|step| *step = Step::UNREACHABLE);
+        self.samples = 0;
+    }
+
+    /// Push a sample, and return the mean cost along the best path ending at it and the end of the template.
+    /// Paths may start at the sample if `start` is true.
+    ///
+    /// Paths are chosen by their mean cost, so longer paths don't lose to shorter ones
+    /// only because they add up more cells.
+    fn push<F>(
+        &mut self,
+        template: &[IMUSample],
+        sample: &IMUSample,
+        start: bool,
+        cost: F,
+    ) -> Option<f32>
+    where
+        F: Fn(&IMUSample, &IMUSample) -> f32,
+    {
+        let index = self.samples;
+        self.samples += 1;
+        let window = self.window;
+
+        let mut diagonal = self.column[0];
+        self.column[0] = if start {
+            Step {
+                cost: 0.0,
+                length: 0,
+                start: index,
+            }
+        } else {
+            Step::UNREACHABLE
+        };
+
+        for (i, t) in template.iter().enumerate() {
+            let c = cost(t, sample);
+            // Previous sample on the diagonal and to the left, and this sample below
+            let (left, below) = (self.column[i + 1], self.column[i]);
+            let best = [diagonal, left, below]
+                .iter()
+                .copied()
+                .filter(|step| step.cost.is_finite() && index - step.start < window)
+                .min_by(|a, b| a.mean_with(c).total_cmp(&b.mean_with(c)));
+
+            diagonal = left;
+            self.column[i + 1] = match best {
+                Some(step) => Step {
+                    cost: step.cost + c,
+                    length: step.length + 1,
+                    start: step.start,
+                },
+                None => Step::UNREACHABLE,
+            };
+        }
+
+        let end = self.column[template.len()];
+        if end.cost.is_finite() && end.length > 0 {
+            Some(end.cost / end.length as f32)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,398 @@
+//! Orientation of controllers from the IMU(6-Axis sensor).
+//!
+//! Axes are the ones of [`IMUSample`]. See [the IMU module].
+//! The world has Z up, and X and Y are X and Y of the controller lying flat at the beginning or the last recentering.
+//!
+//! # Usage
+//! [`OrientationFilter`] fuses the gyroscope with gravity from the accelerometer.
+//! Keep one filter per controller, and recenter it when the player faces forward.
+//! ```no_run
+//! use joycon_rs::prelude::{*, imu::*, orientation::*};
+//!
+//! fn track(standard_full_mode: &StandardFullMode<SimpleJoyConDriver>, calibrator: &IMUCalibrator) -> JoyConResult<()> {
+//!     let mut filter = OrientationFilter::new(FilterAlgorithm::default());
+//!
+//!     loop {
+//!         let report = standard_full_mode.read_input_report()?;
+//!         filter.update_samples(&report.extra.samples(calibrator));
+//!
+//!         if report.common.pushed_buttons.contains(Buttons::Home) {
+//!             filter.recenter();
+//!         }
+//!         println!("{:?}", filter.euler_angles());
+//!     }
+//! }
+//! ```
+//!
+//! [`OrientationFilter`]: struct.OrientationFilter.html
+//! [`IMUSample`]: ../imu/struct.IMUSample.html
+//! [the IMU module]: ../imu/index.html#axes
+
+use super::imu::{IMUSample, Vector3};
+use super::*;
+use std::time::Duration;
+
+/// Unit quaternion representing a rotation.
+#[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Quaternion::IDENTITY
+    }
+}
+
+impl Quaternion {
+    pub const IDENTITY: Quaternion = Quaternion {
+        w: 1.0,
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+
+    pub fn new(w: f32, x: f32, y: f32, z: f32) -> Self {
+        Quaternion { w, x, y, z }
+    }
+
+    /// Rotation of `angle` radians around `axis`.
+    pub fn from_axis_angle(axis: Vector3, angle: f32) -> Self {
+        let axis = axis.normalized().unwrap_or_default();
+        let (sin, cos) = (angle / 2.0).sin_cos();
+
+        Quaternion::new(cos, axis.x * sin, axis.y * sin, axis.z * sin)
+    }
+
+    /// Rotation of yaw around Z, then pitch around Y, then roll around X.
+    pub fn from_euler_angles(angles: EulerAngles) -> Self {
+        let (sr, cr) = (angles.roll / 2.0).sin_cos();
+        let (sp, cp) = (angles.pitch / 2.0).sin_cos();
+        let (sy, cy) = (angles.yaw / 2.0).sin_cos();
+
+        Quaternion {
+            w: cr * cp * cy + sr * sp * sy,
+            x: sr * cp * cy - cr * sp * sy,
+            y: cr * sp * cy + sr * cp * sy,
+            z: cr * cp * sy - sr * sp * cy,
+        }
+    }
+
+    pub fn conjugate(&self) -> Self {
+        Quaternion::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    pub fn norm(&self) -> f32 {
+        (self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+    }
+
+    /// Quaternion with norm 1. Identity for the zero quaternion.
+    pub fn normalized(&self) -> Self {
+        let norm = self.norm();
+        if norm > 0.0 {
+            Quaternion::new(self.w / norm, self.x / norm, self.y / norm, self.z / norm)
+        } else {
+            Quaternion::IDENTITY
+        }
+    }
+
+    /// Rotate a vector.
+    pub fn rotate(&self, vector: Vector3) -> Vector3 {
+        let v = Quaternion::new(0.0, vector.x, vector.y, vector.z);
+        let rotated = *self * v * self.conjugate();
+
+        Vector3::new(rotated.x, rotated.y, rotated.z)
+    }
+
+    /// Angles in order of yaw around Z, pitch around Y and roll around X.
+    pub fn euler_angles(&self) -> EulerAngles {
+        let Quaternion { w, x, y, z } = *self;
+
+        EulerAngles {
+            roll: (2.0 * (w * x + y * z)).atan2(1.0 - 2.0 * (x * x + y * y)),
+            pitch: (2.0 * (w * y - z * x)).clamp(-1.0, 1.0).asin(),
+            yaw: (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z)),
+        }
+    }
+}
+
+impl std::ops::Mul for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, rhs: Quaternion) -> Quaternion {
+        Quaternion {
+            w: self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            x: self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            y: self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            z: self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        }
+    }
+}
+
+/// Euler angles in radians.
+#[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EulerAngles {
+    /// Rotation around X.
+    pub roll: f32,
+    /// Rotation around Y.
+    pub pitch: f32,
+    /// Rotation around Z.
+    pub yaw: f32,
+}
+
+/// Algorithm of [`OrientationFilter`].
+///
+/// [`OrientationFilter`]: struct.OrientationFilter.html
+#[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterAlgorithm {
+    /// Madgwick's gradient descent filter.
+    /// Larger `beta` follows the accelerometer faster, but is noisier.
+    Madgwick { beta: f32 },
+    /// Mahony's complementary filter.
+    /// `kp` is the proportional gain and `ki` is the integral gain for gyro bias.
+    Mahony { kp: f32, ki: f32 },
+}
+
+impl Default for FilterAlgorithm {
+    fn default() -> Self {
+        FilterAlgorithm::Madgwick { beta: 0.1 }
+    }
+}
+
+/// Estimates orientation of a controller from IMU samples.
+///
+/// The first sample initializes roll and pitch from the accelerometer.
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{imu::*, orientation::*};
+/// use std::time::Duration;
+///
+/// let mut filter = OrientationFilter::new(FilterAlgorithm::default());
+///
+/// // Turning left at 90dps for a second, lying flat
+/// let sample = IMUSample {
+///     acceleration: Vector3::new(0.0, 0.0, 1.0),
+///     angular_velocity: Vector3::new(0.0, 0.0, 90.0),
+/// };
+/// for _ in 0..200 {
+///     filter.update(&sample, Duration::from_millis(5));
+/// }
+///
+/// let angles = filter.euler_angles();
+/// assert!((angles.yaw.to_degrees() - 90.0).abs() < 1.0);
+/// assert!(angles.pitch.abs() < 0.01);
+/// assert!((filter.gravity().z + 1.0).abs() < 0.01);
+///
+/// filter.recenter();
+/// assert!(filter.euler_angles().yaw.abs() < 0.01);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrientationFilter {
+    algorithm: FilterAlgorithm,
+    sample_period: Duration,
+    /// Rotation from the controller to the world.
+    quaternion: Quaternion,
+    /// Heading removed by recentering.
+    reference: Quaternion,
+    /// Integral of errors for Mahony.
+    integral_error: Vector3,
+    initialized: bool,
+}
+
+impl OrientationFilter {
+    /// Period of the samples in a standard full report.
+    pub const DEFAULT_SAMPLE_PERIOD: Duration = IMUSample::PERIOD;
+
+    pub fn new(algorithm: FilterAlgorithm) -> Self {
+        OrientationFilter {
+            algorithm,
+            sample_period: Self::DEFAULT_SAMPLE_PERIOD,
+            quaternion: Quaternion::IDENTITY,
+            reference: Quaternion::IDENTITY,
+            integral_error: Vector3::default(),
+            initialized: false,
+        }
+    }
+
+    pub fn algorithm(&self) -> FilterAlgorithm {
+        self.algorithm
+    }
+
+    /// Change the algorithm or its gain. The orientation is kept.
+    pub fn set_algorithm(&mut self, algorithm: FilterAlgorithm) {
+        self.algorithm = algorithm;
+        self.integral_error = Vector3::default();
+    }
+
+    /// Period between samples passed to `update_samples`.
+    pub fn sample_period(&self) -> Duration {
+        self.sample_period
+    }
+
+    pub fn set_sample_period(&mut self, sample_period: Duration) {
+        self.sample_period = sample_period;
+    }
+
+    /// Update with samples of a report, `sample_period` apart.
+    pub fn update_samples(&mut self, samples: &[IMUSample]) {
+        let period = self.sample_period;
+        IMUSample::fold_in_order(samples, (), |(), sample| self.update(sample, period));
+    }
+
+    /// Update with a sample measured `dt` after the previous one.
+    pub fn update(&mut self, sample: &IMUSample, dt: Duration) {
+        if !self.initialized {
+            if let Some(a) = sample.acceleration.normalized() {
+                self.quaternion = Quaternion::from_euler_angles(EulerAngles {
+                    roll: a.y.atan2(a.z),
+                    pitch: (-a.x).atan2(a.y.hypot(a.z)),
+                    yaw: 0.0,
+                });
+                self.initialized = true;
+            }
+        }
+
+        let gyro = sample.angular_velocity_rads();
+        let dt = dt.as_secs_f32();
+        let accel = sample.acceleration.normalized();
+
+        self.quaternion = match self.algorithm {
+            FilterAlgorithm::Madgwick { beta } => madgwick(self.quaternion, gyro, accel, beta, dt),
+            FilterAlgorithm::Mahony { kp, ki } => mahony(
+                self.quaternion,
+                &mut self.integral_error,
+                gyro,
+                accel,
+                (kp, ki),
+                dt,
+            ),
+        };
+    }
+
+    /// Orientation relative to the last recentering.
+    pub fn orientation(&self) -> Quaternion {
+        (self.reference.conjugate() * self.quaternion).normalized()
+    }
+
+    /// Angles of `orientation()`.
+    pub fn euler_angles(&self) -> EulerAngles {
+        self.orientation().euler_angles()
+    }
+
+    /// Direction of gravity in axes of the controller, in G.
+    pub fn gravity(&self) -> Vector3 {
+        self.quaternion
+            .conjugate()
+            .rotate(Vector3::new(0.0, 0.0, -1.0))
+    }
+
+    /// Make the current heading the front. Roll and pitch are kept, since gravity defines them.
+    pub fn recenter(&mut self) {
+        let yaw = self.quaternion.euler_angles().yaw;
+        self.reference = Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), yaw);
+    }
+
+    /// Forget the orientation. The next sample initializes it again.
+    pub fn reset(&mut self) {
+        *self = OrientationFilter {
+            sample_period: self.sample_period,
+            ..OrientationFilter::new(self.algorithm)
+        };
+    }
+}
+
+/// Rate of change of `q` rotating at `gyro` radians per second.
+fn derivative(q: Quaternion, gyro: Vector3) -> Quaternion {
+    let rate = q * Quaternion::new(0.0, gyro.x, gyro.y, gyro.z);
+    Quaternion::new(rate.w * 0.5, rate.x * 0.5, rate.y * 0.5, rate.z * 0.5)
+}
+
+fn integrate(q: Quaternion, rate: Quaternion, dt: f32) -> Quaternion {
+    Quaternion::new(
+        q.w + rate.w * dt,
+        q.x + rate.x * dt,
+        q.y + rate.y * dt,
+        q.z + rate.z * dt,
+    )
+    .normalized()
+}
+
+fn madgwick(
+    q: Quaternion,
+    gyro: Vector3,
+    accel: Option<Vector3>,
+    beta: f32,
+    dt: f32,
+) -> Quaternion {
+    let mut rate = derivative(q, gyro);
+
+    if let Some(a) = accel {
+        let Quaternion {
+            w: q0,
+            x: q1,
+            y: q2,
+            z: q3,
+        } = q;
+
+        // Gradient of the error between measured and estimated gravity
+        let s0 = 4.0 * q0 * q2 * q2 + 2.0 * q2 * a.x + 4.0 * q0 * q1 * q1 - 2.0 * q1 * a.y;
+        let s1 =
+            4.0 * q1 * q3 * q3 - 2.0 * q3 * a.x + 4.0 * q0 * q0 * q1 - 2.0 * q0 * a.y - 4.0 * q1
+                + 8.0 * q1 * q1 * q1
+                + 8.0 * q1 * q2 * q2
+                + 4.0 * q1 * a.z;
+        let s2 =
+            4.0 * q0 * q0 * q2 + 2.0 * q0 * a.x + 4.0 * q2 * q3 * q3 - 2.0 * q3 * a.y - 4.0 * q2
+                + 8.0 * q2 * q1 * q1
+                + 8.0 * q2 * q2 * q2
+                + 4.0 * q2 * a.z;
+        let s3 = 4.0 * q1 * q1 * q3 - 2.0 * q1 * a.x + 4.0 * q2 * q2 * q3 - 2.0 * q2 * a.y;
+
+        let step = Quaternion::new(s0, s1, s2, s3);
+        if step.norm() > 0.0 {
+            let step = step.normalized();
+            rate = Quaternion::new(
+                rate.w - beta * step.w,
+                rate.x - beta * step.x,
+                rate.y - beta * step.y,
+                rate.z - beta * step.z,
+            );
+        }
+    }
+
+    integrate(q, rate, dt)
+}
+
+fn mahony(
+    q: Quaternion,
+    integral_error: &mut Vector3,
+    gyro: Vector3,
+    accel: Option<Vector3>,
+    (kp, ki): (f32, f32),
+    dt: f32,
+) -> Quaternion {
+    let mut gyro = gyro;
+
+    if let Some(a) = accel {
+        // Estimated direction of gravity, against which the accelerometer measures
+        let v = q.conjugate().rotate(Vector3::new(0.0, 0.0, 1.0));
+        let error = a.cross(&v);
+
+        if ki > 0.0 {
+            *integral_error = *integral_error + error.scale(ki * dt);
+            gyro = gyro + *integral_error;
+        } else {
+            *integral_error = Vector3::default();
+        }
+        gyro = gyro + error.scale(kp);
+    }
+
+    integrate(q, derivative(q, gyro), dt)
+}
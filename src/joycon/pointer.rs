@@ -0,0 +1,251 @@
+//! Use controllers as pointers with the gyroscope.
+//!
+//! # Usage
+//! [`PointerController`] turns the gyroscope into cursor motion.
+//! Hold a clutch button to move the controller without moving the cursor.
+//! ```no_run
+//! use joycon_rs::prelude::{*, imu::*, pointer::*};
+//!
+//! fn point(driver: SimpleJoyConDriver) -> JoyConResult<()> {
+//!     let mut pointer = PointerController::new(driver.joycon().device_type(), driver.rotation);
+//!     pointer.clutch_buttons = vec![Buttons::ZR];
+//!
+//!     let mut calibrator = IMUCalibrator::new(&driver.joycon(), driver.imu_config().unwrap_or_default());
+//!     calibrator.set_aligned(true);
+//!     let standard_full_mode = StandardFullMode::new(driver)?;
+//!
+//!     loop {
+//!         let report = standard_full_mode.read_input_report()?;
+//!         let samples = report.extra.samples(&calibrator);
+//!         let delta = pointer.update_samples(&samples, report.common.buttons());
+//!         println!("Move the cursor by ({}, {})", delta.x, delta.y);
+//!     }
+//! }
+//! ```
+//!
+//! [`PointerController`]: struct.PointerController.html
+
+use super::imu::{IMUSample, Vector3};
+use super::input_report_mode::ButtonSet;
+use super::stick::shaping::LookupTable;
+use super::*;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Motion of a cursor. Right and down are positive, like screen coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PointerDelta {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Multiplier of sensitivity by angular speed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointerAcceleration {
+    /// Always 1.0.
+    None,
+    /// `min_multiplier` under `min_speed` dps, `max_multiplier` over `max_speed` dps, and linear between them.
+    Linear {
+        min_speed: f32,
+        max_speed: f32,
+        min_multiplier: f32,
+        max_multiplier: f32,
+    },
+    /// `(speed in dps, multiplier)` points.
+    Table(LookupTable),
+}
+
+impl Default for PointerAcceleration {
+    fn default() -> Self {
+        PointerAcceleration::Linear {
+            min_speed: 5.0,
+            max_speed: 75.0,
+            min_multiplier: 0.5,
+            max_multiplier: 1.5,
+        }
+    }
+}
+
+impl PointerAcceleration {
+    pub fn multiplier(&self, speed: f32) -> f32 {
+        match self {
+            PointerAcceleration::None => 1.0,
+            PointerAcceleration::Linear {
+                min_speed,
+                max_speed,
+                min_multiplier,
+                max_multiplier,
+            } => {
+                let ratio = if max_speed > min_speed {
+                    ((speed - min_speed) / (max_speed - min_speed)).clamp(0.0, 1.0)
+                } else if speed >= *max_speed {
+                    1.0
+                } else {
+                    0.0
+                };
+                min_multiplier + (max_multiplier - min_multiplier) * ratio
+            }
+            PointerAcceleration::Table(table) => table.apply(speed),
+        }
+    }
+}
+
+/// Smooths slow motion such as hand tremor, keeping fast motion direct.
+///
+/// Motion slower than `threshold / 2` dps is averaged over `window` samples,
+/// motion faster than `threshold` dps is direct, and motion between them is blended.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TremorSmoothing {
+    pub threshold: f32,
+    pub window: usize,
+}
+
+impl Default for TremorSmoothing {
+    fn default() -> Self {
+        TremorSmoothing {
+            threshold: 5.0,
+            window: 16,
+        }
+    }
+}
+
+/// Turns angular velocity into motion of a cursor.
+///
+/// Samples should be aligned by [`IMUCalibrator::set_aligned`].
+/// Turning left and right moves the cursor horizontally, and tilting the front end up and down moves it vertically.
+/// The front end is the shoulder buttons in `Rotation::Portrait`,
+/// and the SL/SR rail of Joy-Cons in `Rotation::Landscape`.
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{*, imu::*, pointer::*};
+/// use std::time::Duration;
+///
+/// let mut pointer = PointerController::new(JoyConDeviceType::JoyConL, Rotation::Portrait);
+/// pointer.sensitivity = 10.0;
+/// pointer.acceleration = PointerAcceleration::None;
+/// pointer.smoothing = None;
+///
+/// // Turning right at 100dps
+/// let sample = IMUSample {
+///     acceleration: Vector3::new(0.0, 0.0, 1.0),
+///     angular_velocity: Vector3::new(0.0, 0.0, -100.0),
+/// };
+/// let delta = pointer.update(&sample, Duration::from_millis(10), false);
+/// assert!((delta.x - 10.0).abs() < 1e-4);
+/// assert_eq!(delta.y, 0.0);
+///
+/// // Clutched
+/// assert_eq!(pointer.update(&sample, Duration::from_millis(10), true), PointerDelta::default());
+/// ```
+///
+/// [`IMUCalibrator::set_aligned`]: ../imu/struct.IMUCalibrator.html#method.set_aligned
+#[derive(Debug, Clone)]
+pub struct PointerController {
+    /// Pixels per degree.
+    pub sensitivity: f32,
+    pub acceleration: PointerAcceleration,
+    /// `None` doesn't smooth.
+    pub smoothing: Option<TremorSmoothing>,
+    /// Motion pauses while any of them are pushed.
+    pub clutch_buttons: Vec<Buttons>,
+    /// Period between samples passed to `update_samples`.
+    pub sample_period: Duration,
+    device_type: JoyConDeviceType,
+    rotation: Rotation,
+    /// Recent slow motion in dps.
+    smoothing_buffer: VecDeque<(f32, f32)>,
+}
+
+impl PointerController {
+    pub fn new(device_type: JoyConDeviceType, rotation: Rotation) -> Self {
+        PointerController {
+            sensitivity: 20.0,
+            acceleration: PointerAcceleration::default(),
+            smoothing: Some(TremorSmoothing::default()),
+            clutch_buttons: Vec::new(),
+            sample_period: IMUSample::PERIOD,
+            device_type,
+            rotation,
+            smoothing_buffer: VecDeque::new(),
+        }
+    }
+
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+    }
+
+    /// Update with samples of a report, and sum their motion.
+    pub fn update_samples(&mut self, samples: &[IMUSample], buttons: ButtonSet) -> PointerDelta {
+        let clutched = self
+            .clutch_buttons
+            .iter()
+            .any(|&button| buttons.contains(button));
+        let period = self.sample_period;
+
+        IMUSample::fold_in_order(samples, PointerDelta::default(), |sum, sample| {
+            let delta = self.update(sample, period, clutched);
+            PointerDelta {
+                x: sum.x + delta.x,
+                y: sum.y + delta.y,
+            }
+        })
+    }
+
+    /// Update with a sample measured `dt` after the previous one.
+    pub fn update(&mut self, sample: &IMUSample, dt: Duration, clutched: bool) -> PointerDelta {
+        if clutched {
+            self.smoothing_buffer.clear();
+            return PointerDelta::default();
+        }
+
+        let (yaw, pitch) = self.yaw_pitch(sample.angular_velocity);
+        let (yaw, pitch) = self.smooth(yaw, pitch);
+
+        let speed = yaw.hypot(pitch);
+        let scale = self.sensitivity * self.acceleration.multiplier(speed) * dt.as_secs_f32();
+
+        // Turning left is positive yaw, and tilting down is positive pitch
+        PointerDelta {
+            x: -yaw * scale,
+            y: pitch * scale,
+        }
+    }
+
+    /// Angular velocity turning left, and tilting the front end down.
+    fn yaw_pitch(&self, gyro: Vector3) -> (f32, f32) {
+        match (self.rotation, &self.device_type) {
+            (Rotation::Landscape, JoyConDeviceType::JoyConL) => (gyro.z, gyro.x),
+            (Rotation::Landscape, JoyConDeviceType::JoyConR) => (gyro.z, -gyro.x),
+            _ => (gyro.z, gyro.y),
+        }
+    }
+
+    fn smooth(&mut self, yaw: f32, pitch: f32) -> (f32, f32) {
+        let smoothing = match self.smoothing {
+            Some(smoothing) if smoothing.window > 0 && smoothing.threshold > 0.0 => smoothing,
+            _ => return (yaw, pitch),
+        };
+
+        let half = smoothing.threshold / 2.0;
+        let direct = ((yaw.hypot(pitch) - half) / half).clamp(0.0, 1.0);
+
+        self.smoothing_buffer
+            .push_back((yaw * (1.0 - direct), pitch * (1.0 - direct)));
+        while self.smoothing_buffer.len() > smoothing.window {
+            self.smoothing_buffer.pop_front();
+        }
+
+        let n = self.smoothing_buffer.len() as f32;
+        let (sum_yaw, sum_pitch) = self
+            .smoothing_buffer
+            .iter()
+            .fold((0.0, 0.0), |(y, p), (dy, dp)| (y + dy, p + dp));
+
+        (yaw * direct + sum_yaw / n, pitch * direct + sum_pitch / n)
+    }
+}
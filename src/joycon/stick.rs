@@ -0,0 +1,508 @@
+//! Calibrated analog stick values.
+//!
+//! Raw [`AnalogStickData`] is 12-bit, and its center and range differ between devices.
+//! [`StickCalibrator`] normalizes it into [`StickPosition`]s in -1.0 - 1.0
+//! with the calibration stored in the device.
+//!
+//! Dead zones and response curves are applied per stick by [`StickShaping`].
+//!
+//! # Usage
+//! ```no_run
+//! use joycon_rs::prelude::{*, stick::*};
+//!
+//! fn print_sticks(driver: SimpleJoyConDriver) -> JoyConResult<()> {
+//!     let calibrator = StickCalibrator::new(&driver.joycon());
+//!     let standard_full_mode = StandardFullMode::new(driver)?;
+//!
+//!     loop {
+//!         let report = standard_full_mode.read_input_report()?;
+//!         let sticks = report.common.sticks(&calibrator);
+//!         println!("left {:?}, right {:?}", sticks.left, sticks.right);
+//!     }
+//! }
+//! ```
+//!
+//! [`AnalogStickData`]: ../input_report_mode/struct.AnalogStickData.html
+//! [`StickCalibrator`]: struct.StickCalibrator.html
+//! [`StickPosition`]: struct.StickPosition.html
+//! [`StickShaping`]: shaping/struct.StickShaping.html
+
+use super::device::calibration::stick::{AxisCalibration, StickCalibration};
+use super::input_report_mode::{AnalogStickData, CommonReport};
+use super::*;
+use shaping::StickShaping;
+
+/// Position of a stick. Right and up are positive.
+#[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StickPosition {
+    /// -1.0 <= x <= 1.0
+    pub x: f32,
+    /// -1.0 <= y <= 1.0
+    pub y: f32,
+}
+
+impl StickPosition {
+    pub fn new(x: f32, y: f32) -> Self {
+        StickPosition { x, y }
+    }
+
+    /// Distance from the center.
+    pub fn magnitude(&self) -> f32 {
+        self.x.hypot(self.y)
+    }
+
+    /// Angle from the right, counterclockwise in radians.
+    pub fn angle(&self) -> f32 {
+        self.y.atan2(self.x)
+    }
+}
+
+/// Positions of the sticks the device has.
+#[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Sticks {
+    /// `None` for right Joy-Con.
+    pub left: Option<StickPosition>,
+    /// `None` for left Joy-Con.
+    pub right: Option<StickPosition>,
+}
+
+/// Calibration of an axis in raw values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Axis {
+    min: i16,
+    center: i16,
+    max: i16,
+}
+
+impl Axis {
+    /// Used if the device has no calibration.
+    const NOMINAL: Axis = Axis {
+        min: 0x800 - 0x640,
+        center: 0x800,
+        max: 0x800 + 0x640,
+    };
+
+    fn normalize(&self, raw: u16) -> f32 {
+        let offset = raw as f32 - self.center as f32;
+        let range = if offset >= 0.0 {
+            self.max - self.center
+        } else {
+            self.center - self.min
+        };
+
+        if range <= 0 {
+            return 0.0;
+        }
+        (offset / range as f32).clamp(-1.0, 1.0)
+    }
+}
+
+impl From<&AxisCalibration> for Axis {
+    fn from(calibration: &AxisCalibration) -> Self {
+        Axis {
+            min: calibration.min(),
+            center: calibration.center(),
+            max: calibration.max(),
+        }
+    }
+}
+
+/// Calibration of a stick in raw values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct StickAxes {
+    x: Axis,
+    y: Axis,
+}
+
+impl StickAxes {
+    /// User calibration if available, otherwise factory calibration.
+    fn select(user: &StickCalibration, factory: &StickCalibration) -> Self {
+        match (user, factory) {
+            (StickCalibration::Available { x, y }, _)
+            | (StickCalibration::Unavailable, StickCalibration::Available { x, y }) => StickAxes {
+                x: x.into(),
+                y: y.into(),
+            },
+            _ => StickAxes {
+                x: Axis::NOMINAL,
+                y: Axis::NOMINAL,
+            },
+        }
+    }
+}
+
+/// Converts raw stick data into calibrated positions.
+///
+/// It uses the user calibration of the device if available, otherwise the factory calibration.
+/// Positions in the dead zone of the device are the center.
+/// Then each stick is shaped with its own [`StickShaping`],
+/// which only clamps positions to the unit circle by default.
+///
+/// [`StickShaping`]: shaping/struct.StickShaping.html
+///
+/// # Example
+/// ```
+/// use joycon_rs::prelude::{*, input_report_mode::AnalogStickData, stick::*};
+///
+/// let calibrator = StickCalibrator::nominal(JoyConDeviceType::ProCon);
+///
+/// let center = AnalogStickData { horizontal: 0x800, vertical: 0x800 };
+/// assert_eq!(calibrator.left(&center), Some(StickPosition::new(0.0, 0.0)));
+///
+/// let right = AnalogStickData { horizontal: 0xE40, vertical: 0x800 };
+/// assert_eq!(calibrator.left(&right), Some(StickPosition::new(1.0, 0.0)));
+///
+/// // Left Joy-Con has no right stick
+/// let calibrator = StickCalibrator::nominal(JoyConDeviceType::JoyConL);
+/// assert_eq!(calibrator.right(&right), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct StickCalibrator {
+    device_type: JoyConDeviceType,
+    left: StickAxes,
+    right: StickAxes,
+    /// Radius of the dead zone in raw values.
+    dead_zone: u16,
+    left_shaping: StickShaping,
+    right_shaping: StickShaping,
+}
+
+impl StickCalibrator {
+    /// Dead zone used if the device has no calibration.
+    pub const NOMINAL_DEAD_ZONE: u16 = 0xAE;
+
+    /// Calibrator with the calibration of the device.
+    pub fn new(device: &JoyConDevice) -> Self {
+        let user = device.stick_user_calibration();
+        let factory = device.stick_factory_calibration();
+
+        StickCalibrator {
+            device_type: device.device_type(),
+            left: StickAxes::select(user.left(), factory.left()),
+            right: StickAxes::select(user.right(), factory.right()),
+            dead_zone: device.stick_parameters().dead_zone(),
+            left_shaping: StickShaping::default(),
+            right_shaping: StickShaping::default(),
+        }
+    }
+
+    /// Calibrator with typical calibration, for devices without calibration.
+    pub fn nominal(device_type: JoyConDeviceType) -> Self {
+        let axes = StickAxes {
+            x: Axis::NOMINAL,
+            y: Axis::NOMINAL,
+        };
+
+        StickCalibrator {
+            device_type,
+            left: axes,
+            right: axes,
+            dead_zone: Self::NOMINAL_DEAD_ZONE,
+            left_shaping: StickShaping::default(),
+            right_shaping: StickShaping::default(),
+        }
+    }
+
+    pub fn device_type(&self) -> JoyConDeviceType {
+        self.device_type.clone()
+    }
+
+    /// Radius of the dead zone in raw values.
+    pub fn dead_zone(&self) -> u16 {
+        self.dead_zone
+    }
+
+    pub fn set_dead_zone(&mut self, dead_zone: u16) {
+        self.dead_zone = dead_zone;
+    }
+
+    pub fn left_shaping(&self) -> &StickShaping {
+        &self.left_shaping
+    }
+
+    pub fn set_left_shaping(&mut self, shaping: StickShaping) {
+        self.left_shaping = shaping;
+    }
+
+    pub fn right_shaping(&self) -> &StickShaping {
+        &self.right_shaping
+    }
+
+    pub fn set_right_shaping(&mut self, shaping: StickShaping) {
+        self.right_shaping = shaping;
+    }
+
+    /// Position of the left stick. `None` for right Joy-Con.
+    pub fn left(&self, data: &AnalogStickData) -> Option<StickPosition> {
+        match self.device_type {
+            JoyConDeviceType::JoyConR => None,
+            _ => Some(self.left_shaping.apply(self.normalize(&self.left, data))),
+        }
+    }
+
+    /// Position of the right stick. `None` for left Joy-Con.
+    pub fn right(&self, data: &AnalogStickData) -> Option<StickPosition> {
+        match self.device_type {
+            JoyConDeviceType::JoyConL => None,
+            _ => Some(self.right_shaping.apply(self.normalize(&self.right, data))),
+        }
+    }
+
+    /// Positions of the sticks in the report.
+    pub fn sticks(&self, report: &CommonReport) -> Sticks {
+        Sticks {
+            left: self.left(&report.left_analog_stick_data),
+            right: self.right(&report.right_analog_stick_data),
+        }
+    }
+
+    fn normalize(&self, axes: &StickAxes, data: &AnalogStickData) -> StickPosition {
+        let dx = data.horizontal as f32 - axes.x.center as f32;
+        let dy = data.vertical as f32 - axes.y.center as f32;
+        if dx.hypot(dy) < self.dead_zone as f32 {
+            return StickPosition::default();
+        }
+
+        StickPosition {
+            x: axes.x.normalize(data.horizontal),
+            y: axes.y.normalize(data.vertical),
+        }
+    }
+}
+
+impl CommonReport {
+    /// Calibrated positions of the sticks. See [`StickCalibrator`].
+    ///
+    /// [`StickCalibrator`]: ../stick/struct.StickCalibrator.html
+    pub fn sticks(&self, calibrator: &StickCalibrator) -> Sticks {
+        calibrator.sticks(self)
+    }
+}
+
+pub mod shaping {
+    //! Shape calibrated stick positions.
+    //!
+    //! Set a [`StickShaping`] per stick with [`StickCalibrator::set_left_shaping`] and [`StickCalibrator::set_right_shaping`].
+    //!
+    //! [`StickShaping`]: struct.StickShaping.html
+    //! [`StickCalibrator::set_left_shaping`]: ../struct.StickCalibrator.html#method.set_left_shaping
+    //! [`StickCalibrator::set_right_shaping`]: ../struct.StickCalibrator.html#method.set_right_shaping
+
+    use super::*;
+
+    /// Inner dead zone. Radii are in -1.0 - 1.0 of the calibrated position.
+    #[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
+    #[derive(Debug, Clone, Copy, PartialEq, Default)]
+    pub enum DeadZone {
+        #[default]
+        None,
+        /// Positions closer to the center than the radius are the center.
+        Radial(f32),
+        /// Each axis closer to the center than the radius is 0.
+        /// Keeps the stick snapped to the axes.
+        Axial(f32),
+        /// Like `Radial`, but the rest is scaled to start from 0 at the edge of the dead zone.
+        ScaledRadial(f32),
+    }
+
+    /// Curve from the magnitude of the stick to the magnitude of the output. Both in 0.0 - 1.0.
+    #[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub enum ResponseCurve {
+        #[default]
+        Linear,
+        /// `output = input ^ exponent`.
+        /// Exponents over 1.0 give finer control around the center.
+        Exponential(f32),
+        LookupTable(LookupTable),
+    }
+
+    impl ResponseCurve {
+        pub fn apply(&self, input: f32) -> f32 {
+            let input = input.clamp(0.0, 1.0);
+            let output = match self {
+                ResponseCurve::Linear => input,
+                ResponseCurve::Exponential(exponent) => input.powf(exponent.max(0.0)),
+                ResponseCurve::LookupTable(table) => table.apply(input),
+            };
+
+            output.clamp(0.0, 1.0)
+        }
+    }
+
+    /// Piecewise linear curve through `(input, output)` points.
+    ///
+    /// Inputs before the first point or after the last one take the output of the nearest point.
+    /// An empty table is linear.
+    ///
+    /// # Example
+    /// ```
+    /// use joycon_rs::prelude::stick::shaping::*;
+    ///
+    /// let table = LookupTable::new(vec![(1.0, 1.0), (0.0, 0.0), (0.5, 0.2)]);
+    /// assert_eq!(table.apply(0.25), 0.1);
+    /// assert_eq!(table.apply(0.75), 0.6);
+    /// ```
+    #[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct LookupTable {
+        points: Vec<(f32, f32)>,
+    }
+
+    impl LookupTable {
+        /// Points are sorted by inputs. Points with NaN are ignored.
+        pub fn new(mut points: Vec<(f32, f32)>) -> Self {
+            points.retain(|(input, output)| !input.is_nan() && !output.is_nan());
+            points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            LookupTable { points }
+        }
+
+        pub fn points(&self) -> &[(f32, f32)] {
+            &self.points
+        }
+
+        pub fn apply(&self, input: f32) -> f32 {
+            let points = &self.points;
+            let (first, last) = match (points.first(), points.last()) {
+                (Some(first), Some(last)) => (first, last),
+                _ => return input,
+            };
+
+            if input <= first.0 {
+                return first.1;
+            }
+            if input >= last.0 {
+                return last.1;
+            }
+
+            points
+                .windows(2)
+                .find(|pair| input <= pair[1].0)
+                .map(|pair| {
+                    let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+                    if x1 - x0 <= f32::EPSILON {
+                        y1
+                    } else {
+                        y0 + (y1 - y0) * (input - x0) / (x1 - x0)
+                    }
+                })
+                .unwrap_or(last.1)
+        }
+    }
+
+    /// Shaping of a stick.
+    ///
+    /// Positions are shaped in order of
+    /// 1. square to circle remapping
+    /// 2. inner dead zone and outer dead zone
+    /// 3. response curve
+    /// 4. anti-deadzone
+    ///
+    /// Magnitudes are clamped to 1.0, so the default leaves positions as they are
+    /// except those out of the unit circle, such as corners of a square range.
+    ///
+    /// # Example
+    /// ```
+    /// use joycon_rs::prelude::stick::{*, shaping::*};
+    ///
+    /// let shaping = StickShaping::default();
+    /// assert_eq!(shaping.apply(StickPosition::new(0.5, -0.5)), StickPosition::new(0.5, -0.5));
+    ///
+    /// let corner = shaping.apply(StickPosition::new(1.0, 1.0));
+    /// assert!((corner.x - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+    /// assert!((corner.y - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+    ///
+    /// let shaping = StickShaping {
+    ///     dead_zone: DeadZone::ScaledRadial(0.2),
+    ///     outer_dead_zone: 0.2,
+    ///     ..StickShaping::default()
+    /// };
+    ///
+    /// assert_eq!(shaping.apply(StickPosition::new(0.1, 0.0)), StickPosition::new(0.0, 0.0));
+    /// assert_eq!(shaping.apply(StickPosition::new(0.5, 0.0)), StickPosition::new(0.5, 0.0));
+    /// assert_eq!(shaping.apply(StickPosition::new(0.0, -0.9)), StickPosition::new(0.0, -1.0));
+    ///
+    /// // Games with their own dead zone
+    /// let shaping = StickShaping {
+    ///     anti_dead_zone: 0.25,
+    ///     ..StickShaping::default()
+    /// };
+    /// assert_eq!(shaping.apply(StickPosition::new(0.0, 0.0)), StickPosition::new(0.0, 0.0));
+    /// assert_eq!(shaping.apply(StickPosition::new(0.0, 0.2)), StickPosition::new(0.0, 0.4));
+    /// ```
+    #[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct StickShaping {
+        pub dead_zone: DeadZone,
+        /// Width of the rim, from 0.0 - 1.0, where the output is the max.
+        pub outer_dead_zone: f32,
+        /// Magnitude, from 0.0 - 1.0, the output jumps to as soon as it leaves the center.
+        pub anti_dead_zone: f32,
+        pub curve: ResponseCurve,
+        /// Remap the square range of some sticks and third party controllers to a circle.
+        pub square_to_circle: bool,
+    }
+
+    impl StickShaping {
+        pub fn apply(&self, position: StickPosition) -> StickPosition {
+            let StickPosition { mut x, mut y } = position;
+
+            if self.square_to_circle {
+                let (sx, sy) = (x.clamp(-1.0, 1.0), y.clamp(-1.0, 1.0));
+                x = sx * (1.0 - sy * sy / 2.0).sqrt();
+                y = sy * (1.0 - sx * sx / 2.0).sqrt();
+            }
+
+            let mut inner = 0.0;
+            match self.dead_zone {
+                DeadZone::None => {}
+                DeadZone::Radial(radius) => {
+                    if x.hypot(y) < radius {
+                        return StickPosition::default();
+                    }
+                }
+                DeadZone::Axial(radius) => {
+                    if x.abs() < radius {
+                        x = 0.0;
+                    }
+                    if y.abs() < radius {
+                        y = 0.0;
+                    }
+                }
+                DeadZone::ScaledRadial(radius) => {
+                    if x.hypot(y) < radius {
+                        return StickPosition::default();
+                    }
+                    inner = radius.clamp(0.0, 1.0);
+                }
+            }
+
+            let magnitude = x.hypot(y);
+            if magnitude <= 0.0 {
+                return StickPosition::default();
+            }
+
+            let outer = 1.0 - self.outer_dead_zone.clamp(0.0, 1.0);
+            let scaled = if outer - inner <= f32::EPSILON {
+                1.0
+            } else {
+                ((magnitude - inner) / (outer - inner)).clamp(0.0, 1.0)
+            };
+
+            let mut shaped = self.curve.apply(scaled);
+            if shaped > 0.0 {
+                let anti_dead_zone = self.anti_dead_zone.clamp(0.0, 1.0);
+                shaped = anti_dead_zone + (1.0 - anti_dead_zone) * shaped;
+            }
+
+            let ratio = shaped / magnitude;
+            StickPosition {
+                x: x * ratio,
+                y: y * ratio,
+            }
+        }
+    }
+}
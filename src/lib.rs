@@ -179,8 +179,15 @@ pub mod result {
         HidApiError(hidapi::HidError),
         // SubCommandError(SubCommand),
         SubCommandError(u8, Vec<u8>),
+        /// No reply to the sub-command arrived within the retry policy.
+        SubCommandTimeout(u8),
         JoyConDeviceError(JoyConDeviceError),
         JoyConReportError(JoyConReportError),
+        AudioError(AudioError),
+        InvalidRumbleData(InvalidRumbleData),
+        /// The rumble was rejected by `RumbleLimiter`.
+        UnsafeRumble(crate::joycon::Rumble),
+        InvalidHomeLightPattern(InvalidHomeLightPattern),
         Disconnected,
     }
 
@@ -199,6 +206,8 @@ pub mod result {
         FailedIMUOffsetsLoading,
         FailedIMUCalibrationLoading,
         FailedColorLoading,
+        /// The device has no HOME light.
+        NoHomeLight(crate::joycon::JoyConDeviceType),
     }
 
     impl From<JoyConDeviceError> for JoyConError {
@@ -243,6 +252,8 @@ pub mod result {
     pub enum InvalidStandardInputReport {
         InvalidReport(Vec<u8>),
         InvalidExtraReport(Vec<u8>),
+        /// Shorter than the common part. The length of the report.
+        ShortReport(usize),
         Battery(u8),
         ConnectionInfo(u8),
         InvalidInputReportId(u8),
@@ -261,5 +272,60 @@ pub mod result {
         }
     }
 
+    #[derive(Debug)]
+    pub enum AudioError {
+        Io(std::io::Error),
+        /// Not a RIFF WAVE file, or a required chunk is missing.
+        InvalidWav,
+        UnsupportedFormat {
+            format: u16,
+            bits_per_sample: u16,
+        },
+    }
+
+    impl From<std::io::Error> for AudioError {
+        fn from(e: std::io::Error) -> Self {
+            AudioError::Io(e)
+        }
+    }
+
+    impl From<AudioError> for JoyConError {
+        fn from(e: AudioError) -> Self {
+            JoyConError::AudioError(e)
+        }
+    }
+
+    #[derive(Debug)]
+    pub enum InvalidRumbleData {
+        /// Encodings other than the pair of the high band and the low band,
+        /// or frequencies out of the range of the band.
+        UnsupportedEncoding([u8; 4]),
+        InvalidOutputReport(Vec<u8>),
+    }
+
+    impl From<InvalidRumbleData> for JoyConError {
+        fn from(e: InvalidRumbleData) -> Self {
+            JoyConError::InvalidRumbleData(e)
+        }
+    }
+
+    #[derive(Debug)]
+    pub enum InvalidHomeLightPattern {
+        /// Patterns have up to 15 phases.
+        TooManyPhases(usize),
+        /// Durations of phases are multiples of the global mini cycle duration, which is 0.
+        ZeroGlobalMiniCycleDuration,
+        OutOfRange {
+            field: &'static str,
+            value: u32,
+        },
+    }
+
+    impl From<InvalidHomeLightPattern> for JoyConError {
+        fn from(e: InvalidHomeLightPattern) -> Self {
+            JoyConError::InvalidHomeLightPattern(e)
+        }
+    }
+
     pub type JoyConResult<T> = Result<T, JoyConError>;
 }